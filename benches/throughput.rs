@@ -31,7 +31,7 @@ macro_rules! benchmark_suite {
         #[allow(non_snake_case)]
         mod $poly {
             use super::super::*;
-            use crc_complete::polynomial::$poly;
+            use crc_complete::polynomial::algorithm::$poly;
             use crc_complete::hasher::{CrcHasher, $hasher};
             use test::Bencher;
 
@@ -83,4 +83,144 @@ macro_rules! benchmark_suite {
 }
 
 benchmark_suite!(Bitwise);
-benchmark_suite!(Table);
\ No newline at end of file
+benchmark_suite!(Table);
+benchmark_suite!(Nibble);
+benchmark_suite!(HalfByte);
+benchmark_suite!(Slicex4);
+benchmark_suite!(Slicex8);
+benchmark_suite!(Slicex16);
+benchmark_suite!(Pclmul);
+benchmark_suite!(Simd);
+benchmark_suite!(Auto);
+
+#[allow(non_snake_case)]
+#[cfg(test)]
+mod Sse42 {
+    benchmark_suite!(Sse42; CRC_32_C);
+}
+
+/// Benchmarks CRC-32C around the length where `Auto` hands buffers from `Sse42` to `Pclmul`.
+macro_rules! crossover_suite {
+    ($hasher:ident; $($name:ident, $len:expr);*) => (
+        #[allow(non_snake_case)]
+        mod $hasher {
+            use super::super::*;
+            use crc_complete::polynomial::algorithm::CRC_32_C;
+            use crc_complete::hasher::{CrcHasher, $hasher};
+            use test::Bencher;
+
+            $(
+                #[bench]
+                fn $name(b: &mut Bencher) {
+                    b.bytes = $len as u64;
+
+                    let hasher = $hasher::with(&CRC_32_C);
+                    let bytes = &BYTES[..b.bytes as usize];
+                    b.iter(|| {
+                        hasher.finalize_crc(hasher.update_crc(hasher.initial_value(), bytes))
+                    });
+                }
+            )*
+        }
+    )
+}
+
+#[cfg(test)]
+mod crossover {
+    crossover_suite!(Sse42; len_1k, 1024; len_2k, 2048; len_4k, 4096; len_8k, 8192;
+                     len_24k, 3 * 8192; len_64k, 65536);
+    crossover_suite!(Pclmul; len_1k, 1024; len_2k, 2048; len_4k, 4096; len_8k, 8192;
+                     len_24k, 3 * 8192; len_64k, 65536);
+}
+
+/// Benchmarks creating a hasher, which looks its tables up in the cache, against building the
+/// same tables for every hasher.
+macro_rules! construction_suite {
+    ($($hasher:ident, $table:expr);*) => ($(
+        #[allow(non_snake_case)]
+        mod $hasher {
+            use crc_complete::polynomial::algorithm::CRC_32;
+            use crc_complete::hasher::{CrcHasher, $hasher};
+            use crc_complete::hasher::table_builder::CrcTableBuilder;
+            use test::{Bencher, black_box};
+
+            #[bench]
+            fn cached(b: &mut Bencher) {
+                b.iter(|| $hasher::with(black_box(&CRC_32)));
+            }
+
+            #[bench]
+            fn uncached(b: &mut Bencher) {
+                b.iter(|| {
+                    let mut table = $table;
+                    table.build_table(black_box(&CRC_32));
+                    table
+                });
+            }
+        }
+    )*)
+}
+
+#[cfg(test)]
+mod construction {
+    construction_suite!(Slicex4, [[0u32; 256]; 4];
+                        Slicex8, [[0u32; 256]; 8];
+                        Slicex16, [[0u32; 256]; 16]);
+}
+
+/// Benchmarks `crc_many` over 64 buffers of the same length against hashing them one at a time.
+macro_rules! many_suite {
+    ($($name:ident, $len:expr);*) => ($(
+        mod $name {
+            use super::super::*;
+            use crc_complete::many::crc_many;
+            use crc_complete::polynomial::algorithm::CRC_32;
+            use crc_complete::hasher::{CrcHasher, Auto, Table};
+            use test::Bencher;
+
+            fn buffers() -> Vec<&'static [u8]> {
+                (0..64).map(|i| &BYTES[i..i + $len]).collect()
+            }
+
+            #[bench]
+            fn many(b: &mut Bencher) {
+                let buffers = buffers();
+                b.bytes = 64 * $len as u64;
+                b.iter(|| crc_many(&CRC_32, &buffers));
+            }
+
+            #[bench]
+            fn table(b: &mut Bencher) {
+                let buffers = buffers();
+                let hasher = Table::with(&CRC_32);
+                b.bytes = 64 * $len as u64;
+                b.iter(|| {
+                    buffers.iter()
+                        .map(|bytes| {
+                            hasher.finalize_crc(hasher.update_crc(hasher.initial_value(), bytes))
+                        })
+                        .collect::<Vec<_>>()
+                });
+            }
+
+            #[bench]
+            fn auto(b: &mut Bencher) {
+                let buffers = buffers();
+                let hasher = Auto::with(&CRC_32);
+                b.bytes = 64 * $len as u64;
+                b.iter(|| {
+                    buffers.iter()
+                        .map(|bytes| {
+                            hasher.finalize_crc(hasher.update_crc(hasher.initial_value(), bytes))
+                        })
+                        .collect::<Vec<_>>()
+                });
+            }
+        }
+    )*)
+}
+
+#[cfg(test)]
+mod many {
+    many_suite!(len_4, 4; len_8, 8; len_16, 16; len_32, 32; len_64, 64; len_128, 128; len_256, 256; len_4k, 4096);
+}
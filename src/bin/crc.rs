@@ -0,0 +1,1073 @@
+//! Command line tool printing the CRC of files or of the standard input, and writing or
+//! verifying manifests of the CRCs of whole directory trees.
+
+extern crate crc_complete;
+
+use std::convert::TryFrom;
+use std::env;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crc_complete::{Crc8, Crc16, Crc32, Crc64};
+use crc_complete::hasher::{CrcHasher, CrcDigest, Auto, Digest, Cksum};
+use crc_complete::polynomial::{CrcPolynomial, Normal};
+use crc_complete::polynomial::algorithm::{self, Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use crc_complete::polynomial::bytes::CrcBytes;
+
+const USAGE: &str = "\
+Usage: crc [OPTIONS] [FILE]...
+       crc --manifest [OPTIONS] [PATH]...
+       crc --check [OPTIONS] [MANIFEST]...
+
+Prints the CRC of each FILE, or of the standard input when no FILE or `-` is given.
+
+With --manifest, prints a manifest of the CRC of every file under each PATH, starting with a
+header naming the algorithm. Names with backslashes or line breaks are escaped like `sha256sum`
+does, and files whose name is not valid UTF-8 are left out with an error. With --check,
+verifies the files listed in each MANIFEST with the algorithm of its header, or the one given by
+the options when it has none.
+
+Options:
+  -a, --algorithm NAME  use the named algorithm (default: crc-32), see --list
+      --width BITS      width of a custom algorithm: 8, 16, 32 or 64
+      --poly VALUE      polynomial of a custom algorithm, in normal form
+      --init VALUE      initial value of a custom algorithm (default: 0)
+      --refin BOOL      whether a custom algorithm is reflected (default: false)
+      --refout BOOL     must be equal to --refin
+      --xorout VALUE    value XORed into the result of a custom algorithm (default: 0)
+  -f, --format FORMAT   print the CRC as `hex` (default), `dec`, `raw` bytes or like `cksum`,
+                        which also makes `cksum` the default algorithm
+  -m, --manifest        print a manifest of the files under each PATH
+  -c, --check           verify the files listed in each MANIFEST
+      --list            list the named algorithms
+  -h, --help            print this help
+
+Values are decimal, or hexadecimal with a `0x` prefix.";
+
+/// The parameters of an algorithm chosen at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Spec {
+    width: u32,
+    polynomial: u64,
+    initial: u64,
+    reflected: bool,
+    xor_out: u64,
+    /// Whether the length of the message is hashed after it, as `cksum` does.
+    length: bool,
+}
+
+impl Spec {
+    fn of<R, T>(algorithm: &Algorithm<R, T>) -> Spec
+        where R: Reflect,
+              T: Default + Into<u64>,
+              Algorithm<R, T>: CrcAlgorithm<T>
+    {
+        Spec {
+            width: 8 * mem::size_of::<T>() as u32,
+            polynomial: algorithm.polynomial().into(),
+            initial: algorithm.initial().into(),
+            reflected: R::REFLECTED,
+            // Finalizing zero leaves the value the register is XORed with.
+            xor_out: algorithm.finalize_crc(T::default()).into(),
+            length: false,
+        }
+    }
+
+    /// Returns the CRC of everything read from `input`.
+    fn checksum<I: Read>(&self, input: I) -> io::Result<Checksum> {
+        macro_rules! doit {
+            ($($width:expr, $ty:ty);*) => (
+                match (self.width, self.reflected) {
+                    $(
+                        ($width, true) => checksum(&self.algorithm::<Yes, $ty>(
+                            self.initial as $ty, self.polynomial as $ty, self.xor_out as $ty
+                        ), self.length, input),
+                        ($width, false) => checksum(&self.algorithm::<No, $ty>(
+                            self.initial as $ty, self.polynomial as $ty, self.xor_out as $ty
+                        ), self.length, input),
+                    )*
+                    _ => unreachable!("width validated when parsing"),
+                }
+            )
+        }
+
+        doit!(8, Crc8; 16, Crc16; 32, Crc32; 64, Crc64)
+    }
+
+    fn algorithm<R, T>(&self, initial: T, polynomial: T, xor_out: T) -> Algorithm<R, T>
+        where R: Reflect,
+              T: Default,
+              Normal<T>: CrcPolynomial<T>,
+              Algorithm<R, T>: CrcAlgorithm<T>
+    {
+        let polynomial = Normal::with_polynomial(polynomial)
+            .expect("polynomial validated when parsing");
+
+        // The check value is unknown for custom parameters and unused here. The tables the
+        // hashers cache for this polynomial stay alive until exit, which is a single copy here.
+        Algorithm::with_parameters(initial, polynomial, xor_out, T::default())
+    }
+}
+
+impl fmt::Display for Spec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = self.width as usize / 4;
+
+        write!(f, "width={} poly=0x{:0digits$x} init=0x{:0digits$x} refin={} refout={} \
+                   xorout=0x{:0digits$x}",
+               self.width, self.polynomial, self.initial, self.reflected, self.reflected,
+               self.xor_out, digits = digits)?;
+
+        if self.length {
+            write!(f, " length=true")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Spec {
+    /// Parses the parameters printed by `Display`.
+    fn parse(text: &str) -> Result<Spec, String> {
+        let mut custom = Custom::default();
+
+        for parameter in text.split_whitespace() {
+            let (key, value) = match parameter.find('=') {
+                Some(i) => (&parameter[..i], &parameter[i + 1..]),
+                None => return Err(format!("invalid parameter `{}`", parameter)),
+            };
+            let option = format!("--{}", key);
+
+            match key {
+                "width" => custom.width = Some(parse_width(&option, value)?),
+                "poly" => custom.polynomial = Some(parse_number(&option, value)?),
+                "init" => custom.initial = Some(parse_number(&option, value)?),
+                "refin" => custom.refin = Some(parse_bool(&option, value)?),
+                "refout" => custom.refout = Some(parse_bool(&option, value)?),
+                "xorout" => custom.xor_out = Some(parse_number(&option, value)?),
+                "length" => custom.length = Some(parse_bool(&option, value)?),
+                _ => return Err(format!("unknown parameter `{}`", key)),
+            }
+        }
+
+        custom.spec()
+    }
+}
+
+/// The algorithms that can be chosen by name.
+fn presets() -> Vec<(&'static str, Spec)> {
+    vec![("crc-8", Spec::of(&algorithm::CRC_8)),
+         ("crc-8-maxim", Spec::of(&algorithm::CRC_8_MAXIM)),
+         ("crc-16", Spec::of(&algorithm::CRC_16)),
+         ("crc-16-xmodem", Spec::of(&algorithm::CRC_16_XMODEM)),
+         ("crc-32", Spec::of(&algorithm::CRC_32)),
+         ("crc-32c", Spec::of(&algorithm::CRC_32_C)),
+         ("crc-32-mpeg-2", Spec::of(&algorithm::CRC_32_MPEG_2)),
+         ("crc-32-cksum", Spec::of(&algorithm::CRC_32_CKSUM)),
+         ("crc-32-q", Spec::of(&algorithm::CRC_32_Q)),
+         ("crc-64", Spec::of(&algorithm::CRC_64)),
+         ("crc-64-xz", Spec::of(&algorithm::CRC_64_XZ)),
+         ("cksum", Spec { length: true, ..Spec::of(&algorithm::CRC_32_CKSUM) })]
+}
+
+/// Returns the named algorithm, ignoring case and treating `_` like `-`.
+fn preset(name: &str) -> Option<Spec> {
+    let name = name.to_ascii_lowercase().replace('_', "-");
+    presets().into_iter().find(|&(preset, _)| preset == name).map(|(_, spec)| spec)
+}
+
+/// The CRC of a message.
+struct Checksum {
+    value: u64,
+    len: u64,
+    /// The CRC in the byte order it is conventionally stored in.
+    bytes: Vec<u8>,
+}
+
+fn checksum<R, T, I>(algorithm: &Algorithm<R, T>, length: bool, input: I) -> io::Result<Checksum>
+    where R: Reflect + 'static,
+          T: Copy + Into<u64> + 'static,
+          I: Read,
+          Algorithm<R, T>: CrcAlgorithm<T> + CrcBytes<T>,
+          for<'a> Auto<'a, R, T>: CrcHasher<'a, R, T> + Clone
+{
+    let (crc, len) = if length {
+        digest(Cksum::<Auto<_, _>, _, _>::new(algorithm), input)?
+    } else {
+        digest(Digest::<Auto<_, _>, _, _>::new(algorithm), input)?
+    };
+
+    Ok(Checksum {
+        value: crc.into(),
+        len,
+        bytes: algorithm.encode(crc).as_ref().to_vec(),
+    })
+}
+
+/// Feeds everything read from `input` to `digest`, returning the checksum and the number of
+/// bytes read.
+fn digest<D, I>(mut digest: D, mut input: I) -> io::Result<(D::Output, u64)>
+    where D: CrcDigest,
+          I: Read
+{
+    let mut buffer = [0; 64 * 1024];
+    let mut len = 0;
+
+    loop {
+        match input.read(&mut buffer) {
+            Ok(0) => return Ok((digest.finalize(), len)),
+            Ok(read) => {
+                digest.update(&buffer[..read]);
+                len += read as u64;
+            }
+            Err(ref err) if err.kind() == ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Hex,
+    Decimal,
+    Raw,
+    /// The CRC in decimal followed by the length, like `cksum` prints them.
+    Cksum,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Options {
+    spec: Spec,
+    format: Format,
+    /// The operands as given, so empty when the standard input is read implicitly.
+    files: Vec<String>,
+}
+
+impl Options {
+    /// Returns the operands, or `-` for the standard input when none were given.
+    fn operands(&self) -> Vec<String> {
+        if self.files.is_empty() {
+            vec!["-".to_string()]
+        } else {
+            self.files.clone()
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Help,
+    List,
+    Checksum(Options),
+    Manifest(Options),
+    Check(Options),
+}
+
+/// The parameters given one by one with `--width` and the following options.
+#[derive(Default)]
+struct Custom {
+    width: Option<u32>,
+    polynomial: Option<u64>,
+    initial: Option<u64>,
+    refin: Option<bool>,
+    refout: Option<bool>,
+    xor_out: Option<u64>,
+    length: Option<bool>,
+}
+
+impl Custom {
+    fn is_empty(&self) -> bool {
+        self.width.is_none() && self.polynomial.is_none() && self.initial.is_none() &&
+        self.refin.is_none() && self.refout.is_none() && self.xor_out.is_none() &&
+        self.length.is_none()
+    }
+
+    fn spec(&self) -> Result<Spec, String> {
+        let width = self.width.ok_or("--width is required with custom parameters")?;
+        let polynomial = self.polynomial.ok_or("--poly is required with custom parameters")?;
+        let reflected = self.refin.unwrap_or(false);
+
+        if ![8, 16, 32, 64].contains(&width) {
+            return Err(format!("unsupported width {}: expected 8, 16, 32 or 64", width));
+        }
+
+        if self.refout.unwrap_or(reflected) != reflected {
+            return Err("--refin and --refout must be equal".to_string());
+        }
+
+        let spec = Spec {
+            width,
+            polynomial,
+            initial: self.initial.unwrap_or(0),
+            reflected,
+            xor_out: self.xor_out.unwrap_or(0),
+            length: self.length.unwrap_or(false),
+        };
+        let mask = u64::MAX >> (64 - width);
+
+        for &(name, value) in &[("--poly", spec.polynomial),
+                                ("--init", spec.initial),
+                                ("--xorout", spec.xor_out)] {
+            if value & !mask != 0 {
+                return Err(format!("{} 0x{:x} does not fit in {} bits", name, value, width));
+            }
+        }
+
+        if polynomial & 1 == 0 {
+            return Err(format!("--poly 0x{:x} must have its lowest bit set", polynomial));
+        }
+
+        Ok(spec)
+    }
+}
+
+fn parse_number(option: &str, value: &str) -> Result<u64, String> {
+    let parsed = if value.starts_with("0x") || value.starts_with("0X") {
+        u64::from_str_radix(&value[2..], 16)
+    } else {
+        value.parse()
+    };
+
+    parsed.map_err(|_| format!("invalid value `{}` for {}", value, option))
+}
+
+fn parse_width(option: &str, value: &str) -> Result<u32, String> {
+    u32::try_from(parse_number(option, value)?)
+        .map_err(|_| format!("invalid value `{}` for {}", value, option))
+}
+
+fn parse_bool(option: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("invalid value `{}` for {}: expected true or false", value, option)),
+    }
+}
+
+fn parse_args<I>(args: I) -> Result<Command, String>
+    where I: IntoIterator<Item = String>
+{
+    let mut args = args.into_iter();
+    let mut name = None;
+    let mut custom = Custom::default();
+    let mut format = Format::Hex;
+    let mut files = Vec::new();
+    let mut mode: fn(Options) -> Command = Command::Checksum;
+
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            files.extend(&mut args);
+            break;
+        }
+
+        if !arg.starts_with('-') || arg == "-" {
+            files.push(arg);
+            continue;
+        }
+
+        // Both `--option value` and `--option=value` are accepted.
+        let (option, inline) = match arg.find('=') {
+            Some(i) if arg.starts_with("--") => {
+                (arg[..i].to_string(), Some(arg[i + 1..].to_string()))
+            }
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline.clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} requires a value", option))
+        };
+
+        match option.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--list" => return Ok(Command::List),
+            "-m" | "--manifest" => mode = Command::Manifest,
+            "-c" | "--check" => mode = Command::Check,
+            "-a" | "--algorithm" => name = Some(value()?),
+            "--width" => custom.width = Some(parse_width(&option, &value()?)?),
+            "--poly" => custom.polynomial = Some(parse_number(&option, &value()?)?),
+            "--init" => custom.initial = Some(parse_number(&option, &value()?)?),
+            "--refin" => custom.refin = Some(parse_bool(&option, &value()?)?),
+            "--refout" => custom.refout = Some(parse_bool(&option, &value()?)?),
+            "--xorout" => custom.xor_out = Some(parse_number(&option, &value()?)?),
+            "-f" | "--format" => {
+                format = match value()?.as_str() {
+                    "hex" => Format::Hex,
+                    "dec" => Format::Decimal,
+                    "raw" => Format::Raw,
+                    "cksum" => Format::Cksum,
+                    other => return Err(format!("unknown format `{}`", other)),
+                }
+            }
+            _ => return Err(format!("unknown option `{}`", option)),
+        }
+    }
+
+    let spec = match name {
+        Some(_) if !custom.is_empty() => {
+            return Err("--algorithm cannot be combined with custom parameters".to_string())
+        }
+        Some(name) => preset(&name).ok_or_else(|| format!("unknown algorithm `{}`", name))?,
+        None if custom.is_empty() && format == Format::Cksum => {
+            preset("cksum").expect("cksum is a preset")
+        }
+        None if custom.is_empty() => Spec::of(&algorithm::CRC_32),
+        None => custom.spec()?,
+    };
+
+    Ok(mode(Options { spec, format, files }))
+}
+
+/// Returns the CRC of the file at `path`, or of the standard input for `-`.
+fn checksum_file(spec: &Spec, path: &str) -> io::Result<Checksum> {
+    if path == "-" {
+        spec.checksum(io::stdin().lock())
+    } else {
+        File::open(path).and_then(|input| spec.checksum(input))
+    }
+}
+
+/// Prints the CRC of every file, returning the exit code.
+fn checksum_files(options: &Options) -> i32 {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let mut code = 0;
+
+    for file in &options.operands() {
+        let checksum = checksum_file(&options.spec, file);
+
+        let printed = match checksum {
+            Ok(checksum) => print_checksum(options, file, &checksum, &mut stdout),
+            Err(err) => {
+                eprintln!("crc: {}: {}", file, err);
+                code = 1;
+                Ok(())
+            }
+        };
+
+        if let Err(err) = printed {
+            eprintln!("crc: {}", err);
+            return 1;
+        }
+    }
+
+    code
+}
+
+/// Prints the CRC of `file` to `out` in the format of `options`.
+fn print_checksum<W: Write>(options: &Options, file: &str, checksum: &Checksum, out: &mut W)
+                            -> io::Result<()> {
+    match options.format {
+        Format::Hex => {
+            writeln!(out, "{:01$x}  {2}", checksum.value, options.spec.width as usize / 4, file)
+        }
+        Format::Decimal => writeln!(out, "{}  {}", checksum.value, file),
+        Format::Raw => out.write_all(&checksum.bytes),
+        // `cksum` leaves the name out when it reads the standard input for lack of operands.
+        Format::Cksum if options.files.is_empty() => {
+            writeln!(out, "{} {}", checksum.value, checksum.len)
+        }
+        Format::Cksum => writeln!(out, "{} {} {}", checksum.value, checksum.len, file),
+    }
+}
+
+/// Starts the first line of a manifest, followed by the parameters of its algorithm.
+const MANIFEST_HEADER: &str = "# crc ";
+
+/// The entries that could not be read while walking a tree, with the reason.
+type WalkErrors = Vec<(PathBuf, io::Error)>;
+
+/// Appends the files under `path` to `files` in sorted order. A symbolic link to a directory is
+/// followed when it is `path` itself, as it was named on purpose, but not inside the tree so a
+/// link cannot make the walk loop. An entry that cannot be read is added to `errors` and the walk
+/// goes on with the others.
+fn walk(path: &Path, files: &mut Vec<PathBuf>, errors: &mut WalkErrors) {
+    match fs::metadata(path) {
+        Ok(ref metadata) if metadata.is_dir() => walk_dir(path, files, errors),
+        Ok(_) => files.push(path.to_path_buf()),
+        Err(err) => errors.push((path.to_path_buf(), err)),
+    }
+}
+
+fn walk_dir(path: &Path, files: &mut Vec<PathBuf>, errors: &mut WalkErrors) {
+    let mut entries = Vec::new();
+
+    match fs::read_dir(path) {
+        Ok(dir) => {
+            for entry in dir {
+                match entry {
+                    Ok(entry) => entries.push(entry.path()),
+                    Err(err) => errors.push((path.to_path_buf(), err)),
+                }
+            }
+        }
+        Err(err) => errors.push((path.to_path_buf(), err)),
+    }
+
+    entries.sort();
+
+    for entry in entries {
+        match fs::symlink_metadata(&entry) {
+            Ok(ref metadata) if metadata.is_dir() => walk_dir(&entry, files, errors),
+            Ok(_) if !entry.is_dir() => files.push(entry),
+            Ok(_) => {}
+            Err(err) => errors.push((entry, err)),
+        }
+    }
+}
+
+/// Writes the manifest of the files under `paths` to `out`, returning the exit code.
+fn write_manifest<W: Write>(spec: &Spec, paths: &[String], out: &mut W) -> io::Result<i32> {
+    let mut code = 0;
+    writeln!(out, "{}{}", MANIFEST_HEADER, spec)?;
+
+    for path in paths {
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+
+        if path == "-" {
+            files.push(PathBuf::from(path));
+        } else {
+            walk(Path::new(path), &mut files, &mut errors);
+        }
+
+        for (entry, err) in errors {
+            eprintln!("crc: {}: {}", entry.display(), err);
+            code = 1;
+        }
+
+        for file in files {
+            // A lossy name could not be opened again, so the file could never be verified.
+            let file = match file.to_str() {
+                Some(file) => file,
+                None => {
+                    eprintln!("crc: {}: file name is not valid UTF-8", file.display());
+                    code = 1;
+                    continue;
+                }
+            };
+
+            match checksum_file(spec, file) {
+                Ok(checksum) => {
+                    let (prefix, name) = escape(file);
+                    writeln!(out, "{}{:02$x}  {3}", prefix, checksum.value, spec.width as usize / 4,
+                             name)?
+                }
+                Err(err) => {
+                    eprintln!("crc: {}: {}", file, err);
+                    code = 1;
+                }
+            }
+        }
+    }
+
+    Ok(code)
+}
+
+/// Escapes the backslashes and line breaks of `name` like `sha256sum` does, returning the prefix
+/// of the line announcing the escapes and the name to write.
+fn escape(name: &str) -> (&'static str, String) {
+    if !name.contains(['\\', '\n', '\r']) {
+        return ("", name.to_string());
+    }
+
+    let escaped = name.chars().fold(String::new(), |mut escaped, c| {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+        escaped
+    });
+
+    ("\\", escaped)
+}
+
+/// Reads back a name escaped by `escape`, or returns `None` if it has an unknown escape.
+fn unescape(name: &str) -> Option<String> {
+    let mut unescaped = String::new();
+    let mut chars = name.chars();
+
+    while let Some(c) = chars.next() {
+        unescaped.push(match c {
+            '\\' => {
+                match chars.next()? {
+                    '\\' => '\\',
+                    'n' => '\n',
+                    'r' => '\r',
+                    _ => return None,
+                }
+            }
+            c => c,
+        });
+    }
+
+    Some(unescaped)
+}
+
+/// The problems found while verifying a manifest.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Tally {
+    failed: usize,
+    missing: usize,
+    malformed: usize,
+}
+
+/// Verifies the files listed in `manifest`, printing the status of each one to `out`. `spec` is
+/// used when the manifest has no header. A manifest without a single well formed line is an
+/// error, like an empty one.
+fn verify_manifest<B, W>(spec: &Spec, name: &str, manifest: B, out: &mut W) -> io::Result<Tally>
+    where B: BufRead,
+          W: Write
+{
+    let mut spec = *spec;
+    let mut tally = Tally::default();
+    let mut entries = 0;
+
+    for (number, line) in manifest.lines().enumerate() {
+        let line = line?;
+
+        if number == 0 && line.starts_with(MANIFEST_HEADER) {
+            spec = Spec::parse(&line[MANIFEST_HEADER.len()..])
+                .map_err(|message| io::Error::new(ErrorKind::InvalidData, message))?;
+            continue;
+        }
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // A leading backslash announces an escaped name, which is shown as written.
+        let (prefix, entry) = match line.strip_prefix('\\') {
+            Some(entry) => ("\\", entry),
+            None => ("", &line[..]),
+        };
+
+        let entry = entry.find("  ").and_then(|i| {
+            let value = u64::from_str_radix(&entry[..i], 16).ok()?;
+            let name = &entry[i + 2..];
+            let file = if prefix.is_empty() { name.to_string() } else { unescape(name)? };
+            Some((value, name, file))
+        });
+
+        let (expected, name, file) = match entry {
+            Some(entry) => entry,
+            None => {
+                eprintln!("crc: {}:{}: improperly formatted line", name, number + 1);
+                tally.malformed += 1;
+                continue;
+            }
+        };
+
+        entries += 1;
+        match checksum_file(&spec, &file) {
+            Ok(checksum) if checksum.value == expected => writeln!(out, "{}{}: OK", prefix, name)?,
+            Ok(_) => {
+                writeln!(out, "{}{}: FAILED", prefix, name)?;
+                tally.failed += 1;
+            }
+            Err(ref err) if err.kind() == ErrorKind::NotFound => {
+                writeln!(out, "{}{}: MISSING", prefix, name)?;
+                tally.missing += 1;
+            }
+            Err(err) => {
+                eprintln!("crc: {}{}: {}", prefix, name, err);
+                writeln!(out, "{}{}: FAILED", prefix, name)?;
+                tally.failed += 1;
+            }
+        }
+    }
+
+    if entries == 0 {
+        return Err(io::Error::new(ErrorKind::InvalidData, "no properly formatted lines"));
+    }
+
+    Ok(tally)
+}
+
+/// Verifies every manifest, returning the exit code.
+fn check_manifests(options: &Options) -> i32 {
+    let stdout = io::stdout();
+    let mut stdout = stdout.lock();
+    let mut code = 0;
+
+    for name in &options.operands() {
+        let tally = if name == "-" {
+            verify_manifest(&options.spec, name, io::stdin().lock(), &mut stdout)
+        } else {
+            File::open(name).and_then(|manifest| {
+                verify_manifest(&options.spec, name, BufReader::new(manifest), &mut stdout)
+            })
+        };
+
+        match tally {
+            Ok(ref tally) if *tally == Tally::default() => {}
+            Ok(tally) => {
+                for &(count, problem) in &[(tally.failed, "computed checksums did NOT match"),
+                                           (tally.missing, "listed files are missing"),
+                                           (tally.malformed, "lines are improperly formatted")] {
+                    if count > 0 {
+                        eprintln!("crc: WARNING: {} {}", count, problem);
+                    }
+                }
+                code = 1;
+            }
+            Err(err) => {
+                eprintln!("crc: {}: {}", name, err);
+                code = 1;
+            }
+        }
+    }
+
+    code
+}
+
+fn main() {
+    let code = match parse_args(env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{}", USAGE);
+            0
+        }
+        Ok(Command::List) => {
+            for (name, spec) in presets() {
+                println!("{:<14} {}", name, spec);
+            }
+            0
+        }
+        Ok(Command::Checksum(options)) => checksum_files(&options),
+        Ok(Command::Manifest(options)) => {
+            let stdout = io::stdout();
+            let written = write_manifest(&options.spec, &options.operands(), &mut stdout.lock());
+
+            written.unwrap_or_else(|err| {
+                eprintln!("crc: {}", err);
+                1
+            })
+        }
+        Ok(Command::Check(options)) => check_manifests(&options),
+        Err(message) => {
+            eprintln!("crc: {}\nTry `crc --help` for more information.", message);
+            2
+        }
+    };
+
+    process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_args, preset, walk, walk_dir, write_manifest, verify_manifest, escape,
+                unescape, print_checksum, Command, Format, Options, Spec, Tally};
+    use crc_complete::polynomial::algorithm::{CrcAlgorithm, CRC_32_C};
+    use std::env;
+    use std::fs;
+    use std::io::ErrorKind;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn options(line: &str) -> Options {
+        match parse_args(args(line)) {
+            Ok(Command::Checksum(options)) => options,
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn presets_match_their_check_values() {
+        for (_, spec) in super::presets().into_iter().filter(|&(_, spec)| !spec.length) {
+            let checksum = spec.checksum(&b"123456789"[..]).unwrap();
+            let custom = options(&format!("--width {} --poly {} --init {} --refin {} \
+                                           --refout {} --xorout {}",
+                                          spec.width, spec.polynomial, spec.initial,
+                                          spec.reflected, spec.reflected, spec.xor_out));
+            assert!(custom.spec == spec);
+            assert!(custom.spec.checksum(&b"123456789"[..]).unwrap().value == checksum.value);
+        }
+
+        let checksum = preset("CRC_32C").unwrap().checksum(&b"123456789"[..]).unwrap();
+        assert!(checksum.value == CRC_32_C.check().1 as u64);
+        assert!(checksum.bytes == CRC_32_C.check().1.to_le_bytes());
+    }
+
+    #[test]
+    fn arguments() {
+        let parsed = options("-a crc-16 --format=dec a -- -b");
+        assert!(parsed.spec == preset("crc-16").unwrap());
+        assert!(parsed.format == Format::Decimal);
+        assert!(parsed.files == args("a -b"));
+
+        let parsed = options("--width 16 --poly=0x1021");
+        assert!(parsed.spec == Spec {
+            width: 16,
+            polynomial: 0x1021,
+            initial: 0,
+            reflected: false,
+            xor_out: 0,
+            length: false,
+        });
+        assert!(parsed.files.is_empty());
+        assert!(parsed.operands() == args("-"));
+        assert!(options("-").files == args("-"));
+
+        match parse_args(args("--check -a crc-64 sums")) {
+            Ok(Command::Check(options)) => assert!(options.files == args("sums")),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let parsed = options("-f cksum");
+        assert!(parsed.spec == preset("cksum").unwrap());
+        assert!(parsed.format == Format::Cksum);
+        assert!(options("-f cksum -a crc-32").spec == preset("crc-32").unwrap());
+
+        assert!(parse_args(args("--help")) == Ok(Command::Help));
+        assert!(parse_args(args("-f raw --list")) == Ok(Command::List));
+    }
+
+    #[test]
+    fn invalid_arguments() {
+        for line in &["--bogus",
+                      "--algorithm",
+                      "-a crc-7",
+                      "-a crc-32 --width 32",
+                      "--poly 0x07",
+                      "--width 12 --poly 0x07",
+                      "--width 4294967304 --poly 7",
+                      "--width 8 --poly 0x106",
+                      "--width 8 --poly 0x06",
+                      "--width 8 --poly 0x07 --refin true --refout false",
+                      "--width 8 --poly 7 --init seven",
+                      "-f octal"] {
+            assert!(parse_args(args(line)).is_err(), "{}", line);
+        }
+    }
+
+    #[test]
+    fn cksum() {
+        let checksum = preset("cksum").unwrap().checksum(&b"123456789"[..]).unwrap();
+        assert!(checksum.value == 930766865);
+        assert!(checksum.len == 9);
+
+        let checksum = preset("crc-32-cksum").unwrap().checksum(&b"123456789"[..]).unwrap();
+        assert!(checksum.value == 0x765e7680);
+    }
+
+    #[test]
+    fn cksum_output() {
+        let print = |line: &str| {
+            let options = options(line);
+            let checksum = options.spec.checksum(&b"x"[..]).unwrap();
+            let mut out = Vec::new();
+            print_checksum(&options, &options.operands()[0], &checksum, &mut out).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        // Outputs of `printf x | cksum` and `printf x | cksum -`.
+        assert!(print("-f cksum") == "12738659 1\n");
+        assert!(print("-f cksum -") == "12738659 1 -\n");
+        assert!(print("-f cksum file") == "12738659 1 file\n");
+    }
+
+    #[test]
+    fn spec_round_trip() {
+        for (_, spec) in super::presets() {
+            assert!(Spec::parse(&spec.to_string()) == Ok(spec));
+        }
+
+        assert!(Spec::parse("width=32 poly=0x04c11db7 bogus=1").is_err());
+        assert!(Spec::parse("width=32").is_err());
+        assert!(Spec::parse("width=4294967304 poly=7").is_err());
+    }
+
+    #[test]
+    fn manifest() {
+        let root = env::temp_dir().join(format!("crc-manifest-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(root.join("a"), b"123456789").unwrap();
+        fs::write(root.join("sub").join("b"), b"").unwrap();
+        fs::write(root.join("sub").join("c"), b"hello").unwrap();
+
+        let spec = preset("crc-32c").unwrap();
+        let mut manifest = Vec::new();
+        let paths = vec![root.to_string_lossy().into_owned()];
+        assert!(write_manifest(&spec, &paths, &mut manifest).unwrap() == 0);
+
+        let text = String::from_utf8(manifest.clone()).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines.len() == 4);
+        assert!(lines[0] == format!("# crc {}", spec));
+        assert!(lines[1] == format!("e3069283  {}", root.join("a").display()));
+        assert!(lines[3].ends_with(&*root.join("sub").join("c").to_string_lossy()));
+
+        // The header wins over the algorithm given on the command line.
+        let fallback = preset("crc-8").unwrap();
+        let mut out = Vec::new();
+        let tally = verify_manifest(&fallback, "m", &manifest[..], &mut out).unwrap();
+        assert!(tally == Tally::default());
+        assert!(String::from_utf8(out).unwrap().lines().all(|line| line.ends_with(": OK")));
+
+        fs::write(root.join("a"), b"12345678").unwrap();
+        fs::remove_file(root.join("sub").join("c")).unwrap();
+        let manifest = [&manifest[..], b"garbage\n"].concat();
+        let mut out = Vec::new();
+        let tally = verify_manifest(&fallback, "m", &manifest[..], &mut out).unwrap();
+        assert!(tally == Tally { failed: 1, missing: 1, malformed: 1 });
+
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert!(lines[0].ends_with(": FAILED"));
+        assert!(lines[1].ends_with(": OK"));
+        assert!(lines[2].ends_with(": MISSING"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn escaped_names() {
+        assert!(escape("plain") == ("", "plain".to_string()));
+        assert!(escape("a\\b\nc\rd") == ("\\", "a\\\\b\\nc\\rd".to_string()));
+        assert!(unescape("a\\\\b\\nc\\rd") == Some("a\\b\nc\rd".to_string()));
+        assert!(unescape("a\\tb").is_none());
+        assert!(unescape("a\\").is_none());
+
+        let root = env::temp_dir().join(format!("crc-escaped-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("back\\slash"), b"1").unwrap();
+        fs::write(root.join("new\nline"), b"2").unwrap();
+
+        let spec = preset("crc-32").unwrap();
+        let mut manifest = Vec::new();
+        let paths = vec![root.to_string_lossy().into_owned()];
+        assert!(write_manifest(&spec, &paths, &mut manifest).unwrap() == 0);
+
+        let text = String::from_utf8(manifest.clone()).unwrap();
+        assert!(text.lines().count() == 3);
+        assert!(text.lines().skip(1).all(|line| line.starts_with('\\')));
+
+        let mut out = Vec::new();
+        let tally = verify_manifest(&spec, "m", &manifest[..], &mut out).unwrap();
+        assert!(tally == Tally::default());
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.lines().count() == 2);
+        assert!(out.lines().all(|line| line.starts_with('\\') && line.ends_with(": OK")));
+
+        let manifest = [&manifest[..], b"\\00000000  bad\\escape\n"].concat();
+        let tally = verify_manifest(&spec, "m", &manifest[..], &mut Vec::new());
+        assert!(tally.unwrap() == Tally { failed: 0, missing: 0, malformed: 1 });
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn invalid_utf8_name() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let root = env::temp_dir().join(format!("crc-utf8-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("valid"), b"1").unwrap();
+        fs::write(root.join(OsStr::from_bytes(b"in\xffvalid")), b"2").unwrap();
+
+        let spec = preset("crc-32").unwrap();
+        let mut manifest = Vec::new();
+        let paths = vec![root.to_string_lossy().into_owned()];
+        assert!(write_manifest(&spec, &paths, &mut manifest).unwrap() == 1);
+
+        let text = String::from_utf8(manifest).unwrap();
+        assert!(text.lines().count() == 2);
+        assert!(text.ends_with("valid\n"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let root = env::temp_dir().join(format!("crc-symlinks-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("dir")).unwrap();
+        fs::write(root.join("dir").join("a"), b"123456789").unwrap();
+        symlink(root.join("dir"), root.join("link")).unwrap();
+        symlink(&root, root.join("dir").join("loop")).unwrap();
+
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        walk(&root.join("link"), &mut files, &mut errors);
+        assert!(files == [root.join("link").join("a")]);
+
+        let mut files = Vec::new();
+        walk(&root, &mut files, &mut errors);
+        assert!(files == [root.join("dir").join("a")]);
+        assert!(errors.is_empty());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unreadable_entries() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let root = env::temp_dir().join(format!("crc-unreadable-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("locked")).unwrap();
+        fs::write(root.join("a"), b"1").unwrap();
+        fs::write(root.join("locked").join("b"), b"2").unwrap();
+        fs::write(root.join("z"), b"3").unwrap();
+        fs::set_permissions(root.join("locked"), fs::Permissions::from_mode(0o000)).unwrap();
+
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        walk(&root, &mut files, &mut errors);
+
+        // Permissions do not stop root, which reads the locked directory anyway.
+        if fs::read_dir(root.join("locked")).is_err() {
+            assert!(files == [root.join("a"), root.join("z")]);
+            assert!(errors.len() == 1 && errors[0].0 == root.join("locked"));
+        } else {
+            assert!(files == [root.join("a"), root.join("locked").join("b"), root.join("z")]);
+            assert!(errors.is_empty());
+        }
+
+        // A directory that turned into a file or vanished is named and skipped.
+        let mut errors = Vec::new();
+        walk_dir(&root.join("a"), &mut files, &mut errors);
+        walk(&root.join("vanished"), &mut files, &mut errors);
+        assert!(errors.len() == 2);
+        assert!(errors[0].0 == root.join("a") && errors[1].0 == root.join("vanished"));
+        assert!(errors[1].1.kind() == ErrorKind::NotFound);
+
+        let spec = preset("crc-32").unwrap();
+        let mut manifest = Vec::new();
+        let paths = vec![root.join("vanished").to_string_lossy().into_owned(),
+                         root.to_string_lossy().into_owned()];
+        assert!(write_manifest(&spec, &paths, &mut manifest).unwrap() == 1);
+        let text = String::from_utf8(manifest).unwrap();
+        assert!(text.lines().last().unwrap().ends_with(&*root.join("z").to_string_lossy()));
+
+        fs::set_permissions(root.join("locked"), fs::Permissions::from_mode(0o755)).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn empty_manifest() {
+        let spec = preset("crc-32").unwrap();
+
+        for manifest in &[&b""[..], format!("# crc {}\n", spec).as_bytes(), b"garbage\n\n"] {
+            let err = verify_manifest(&spec, "m", *manifest, &mut Vec::new()).unwrap_err();
+            assert!(err.kind() == ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! A hasher that picks the fastest implementation available for every call.
+
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use hasher::{CrcHasher, Slicex16, Pclmul, Sse42};
+use hasher::{pclmul, sse42};
+
+/// Buffers at least this long are folded with PCLMULQDQ when the CPU supports it.
+const FOLD_LEN: usize = 64;
+
+/// Buffers shorter than this use the SSE4.2 `crc32` instruction for CRC-32C, as folding only
+/// overtakes it once the setup of the folding loop is amortized. The `crossover` benchmarks put
+/// the instruction ahead at 1 KiB (11.2 against 10.0 GB/s) and folding ahead from 2 KiB on
+/// (13.0 against 9.7 GB/s, and 17.4 against 13.7 GB/s over the three 8 KiB lanes of `Sse42`),
+/// so those lanes only serve CPUs without PCLMULQDQ.
+const SSE42_LEN: usize = 2048;
+
+/// Hasher choosing between the hardware and the table driven implementations from the features
+/// of the CPU and the length of each buffer.
+///
+/// Only the implementations that can be chosen on this CPU for the algorithm are built.
+#[derive(Clone)]
+pub struct Auto<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    algorithm: &'a Algorithm<R, T>,
+    slicex16: Option<Slicex16<'a, R, T>>,
+    pclmul: Option<Pclmul<'a, R, T>>,
+    sse42: Option<Sse42<'a, Yes, Crc32>>,
+}
+
+impl<'a, R, T> Auto<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    /// Returns the hasher of the buffers no hardware implementation takes.
+    #[inline]
+    fn slicex16(&self) -> &Slicex16<'a, R, T> {
+        self.slicex16.as_ref().expect("built whenever no hardware takes short buffers")
+    }
+}
+
+impl<'a> CrcHasher<'a, Yes, Crc32> for Auto<'a, Yes, Crc32>
+    where Algorithm<Yes, Crc32>: CrcAlgorithm<Crc32>
+{
+    fn with(algorithm: &'a Algorithm<Yes, Crc32>) -> Self {
+        let sse42 = if sse42::is_hardware(algorithm) { Some(Sse42::with(algorithm)) } else { None };
+
+        Auto {
+            algorithm,
+            // The `crc32` instruction takes every buffer folding does not.
+            slicex16: if sse42.is_none() { Some(Slicex16::with(algorithm)) } else { None },
+            pclmul: if pclmul::has_pclmulqdq() { Some(Pclmul::with(algorithm)) } else { None },
+            sse42,
+        }
+    }
+
+    #[inline]
+    fn algorithm(&self) -> &Algorithm<Yes, Crc32> {
+        self.algorithm
+    }
+
+    #[inline]
+    fn initial_value(&self) -> Crc32 {
+        self.algorithm.initial()
+    }
+
+    fn update_crc(&self, crc: Crc32, bytes: &[u8]) -> Crc32 {
+        let fold_len = if self.sse42.is_some() { SSE42_LEN } else { FOLD_LEN };
+
+        match (&self.sse42, &self.pclmul) {
+            (_, Some(pclmul)) if bytes.len() >= fold_len => pclmul.update_crc(crc, bytes),
+            (Some(sse42), _) => sse42.update_crc(crc, bytes),
+            _ => self.slicex16().update_crc(crc, bytes),
+        }
+    }
+
+    #[inline]
+    fn finalize_crc(&self, crc: Crc32) -> Crc32 {
+        self.algorithm.finalize_crc(crc)
+    }
+}
+
+macro_rules! doit {
+    ($reflect:ident; $($ty:ty),*) => ($(
+        impl<'a> CrcHasher<'a, $reflect, $ty> for Auto<'a, $reflect, $ty>
+            where Algorithm<$reflect, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<$reflect, $ty>) -> Self {
+                let pclmul = if pclmul::has_pclmulqdq() {
+                    Some(Pclmul::with(algorithm))
+                } else {
+                    None
+                };
+
+                Auto {
+                    algorithm,
+                    slicex16: Some(Slicex16::with(algorithm)),
+                    pclmul,
+                    sse42: None,
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<$reflect, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                match self.pclmul {
+                    Some(ref pclmul) if bytes.len() >= FOLD_LEN => pclmul.update_crc(crc, bytes),
+                    _ => self.slicex16().update_crc(crc, bytes),
+                }
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+    )*)
+}
+
+doit!(Yes; Crc8, Crc16, Crc64);
+doit!(No; Crc8, Crc16, Crc32, Crc64);
+
+macro_rules! test_auto_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::Auto;
+            use hasher::{CrcHasher, Table};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Auto::with(&$poly);
+                let mut crc = hasher.initial_value();
+                crc = hasher.update_crc(crc, bytes);
+                crc = hasher.finalize_crc(crc);
+                assert!(check_val == crc);
+            }
+
+            #[test]
+            fn lengths() {
+                let bytes: Vec<u8> = (0..10_000u32).map(|i| (i * 31 + i / 251) as u8).collect();
+                let table = Table::with(&$poly);
+                let hasher = Auto::with(&$poly);
+
+                for &len in &[0, 1, 15, 16, 63, 64, 65, 2047, 2048, 2049, 4096, 9999] {
+                    let bytes = &bytes[1..1 + len];
+                    let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                    assert!(table.update_crc(table.initial_value(), bytes) == crc);
+                }
+            }
+        }
+    )*)
+}
+
+test_auto_hasher!(CRC_8,
+                  CRC_8_MAXIM,
+                  CRC_16,
+                  CRC_16_XMODEM,
+                  CRC_32,
+                  CRC_32_C,
+                  CRC_32_MPEG_2,
+                  CRC_32_Q,
+                  CRC_64,
+                  CRC_64_XZ);
+
+#[cfg(test)]
+#[test]
+fn usable_hashers_only() {
+    use polynomial::algorithm::{CRC_32, CRC_32_C, CRC_64};
+
+    let auto = Auto::with(&CRC_32_C);
+    assert!(auto.sse42.is_some() == sse42::is_hardware(&CRC_32_C));
+    assert!(auto.slicex16.is_some() != auto.sse42.is_some());
+    assert!(auto.pclmul.is_some() == pclmul::has_pclmulqdq());
+
+    let auto = Auto::with(&CRC_32);
+    assert!(auto.sse42.is_none() && auto.slicex16.is_some());
+    assert!(Auto::with(&CRC_64).slicex16.is_some());
+}
@@ -1,35 +1,41 @@
 use {Crc8, Crc16, Crc32, Crc64};
 use hasher::CrcHasher;
-use polynomial::{Reflect, Yes, No, CrcPoly, CrcPolynomial};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
 use bit_reverse::ParallelReverse;
 
+#[derive(Clone)]
 pub struct Bitwise<'a, R, T>
     where R: 'a + Reflect,
           T: 'a
 {
-    polynomial: &'a CrcPoly<R, T>,
+    algorithm: &'a Algorithm<R, T>,
 }
 
 macro_rules! doit {
     ($($ty:ty),*) => ($(
         impl<'a> CrcHasher<'a, Yes, $ty> for Bitwise<'a, Yes, $ty>
-            where CrcPoly<Yes, $ty>: CrcPolynomial<$ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
         {
             #[inline]
-            fn with(polynomial: &'a CrcPoly<Yes, $ty>) -> Self {
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
                 Bitwise {
-                    polynomial: polynomial
+                    algorithm,
                 }
             }
 
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                self.algorithm
+            }
+
             #[inline]
             fn initial_value(&self) -> $ty {
-                self.polynomial.init_val()
+                self.algorithm.initial()
             }
 
             fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
                 let mut crc = crc;
-                let polynomial = self.polynomial.poly().swap_bits();
+                let polynomial = self.algorithm.polynomial().swap_bits();
 
                 for &byte in bytes {
                     crc ^= byte as $ty;
@@ -44,28 +50,33 @@ macro_rules! doit {
 
             #[inline]
             fn finalize_crc(&self, crc: $ty) -> $ty {
-                self.polynomial.finalize_crc(crc)
+                self.algorithm.finalize_crc(crc)
             }
         }
 
         impl<'a> CrcHasher<'a, No, $ty> for Bitwise<'a, No, $ty>
-            where CrcPoly<No, $ty>: CrcPolynomial<$ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
         {
             #[inline]
-            fn with(polynomial: &'a CrcPoly<No, $ty>) -> Self {
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
                 Bitwise {
-                    polynomial: polynomial
+                    algorithm,
                 }
             }
 
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                self.algorithm
+            }
+
             #[inline]
             fn initial_value(&self) -> $ty {
-                self.polynomial.init_val()
+                self.algorithm.initial()
             }
 
             fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
                 let mut crc = crc;
-                let polynomial = self.polynomial.poly();
+                let polynomial = self.algorithm.polynomial();
 
                 for &byte in bytes {
                     crc ^=  (byte as $ty).rotate_right(8);
@@ -81,7 +92,7 @@ macro_rules! doit {
 
             #[inline]
             fn finalize_crc(&self, crc: $ty) -> $ty {
-                self.polynomial.finalize_crc(crc)
+                self.algorithm.finalize_crc(crc)
             }
         }
     )*)
@@ -96,7 +107,7 @@ macro_rules! test_bitwise_hasher {
         mod $poly {
             use super::Bitwise;
             use hasher::CrcHasher;
-            use polynomial::{CrcPolynomial, $poly};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
 
             #[test]
             fn check() {
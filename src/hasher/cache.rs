@@ -0,0 +1,145 @@
+//! Process-wide cache of the lookup tables used by the hashers.
+//!
+//! A table only depends on its type, the reflection and the polynomial of an algorithm, so it is
+//! built the first time a hasher asks for it and every later hasher with the same parameters
+//! shares it. Tables are leaked: they are never freed, which bounds the memory used to one copy
+//! per distinct polynomial and table type for the life of the process. A program that hashes with
+//! an unbounded number of polynomials, rather than a few static algorithms, keeps a copy of every
+//! table it ever built.
+//!
+//! A lookup takes a read lock and a hash map probe, which the `construction` benchmarks put at
+//! about 50 ns against 0.5 to 3 µs to build the tables of the slicing hashers again.
+//!
+//! Without std there is nowhere to keep them, so every hasher builds and owns its tables.
+
+use core::any::Any;
+#[cfg(feature = "use_std")]
+use std::any::TypeId;
+#[cfg(feature = "use_std")]
+use std::collections::HashMap;
+#[cfg(feature = "use_std")]
+use std::sync::{OnceLock, RwLock};
+
+use polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
+use hasher::table_builder::CrcTableBuilder;
+
+/// A value handed out by the cache, shared between hashers when std is available.
+#[cfg(feature = "use_std")]
+pub type Cached<V> = &'static V;
+
+/// A value handed out by the cache, owned by each hasher without std.
+#[cfg(not(feature = "use_std"))]
+pub type Cached<V> = V;
+
+#[cfg(feature = "use_std")]
+type Key = (TypeId, TypeId, u64);
+
+#[cfg(feature = "use_std")]
+fn entries() -> &'static RwLock<HashMap<Key, &'static (dyn Any + Send + Sync)>> {
+    static ENTRIES: OnceLock<RwLock<HashMap<Key, &'static (dyn Any + Send + Sync)>>> =
+        OnceLock::new();
+    ENTRIES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Returns the value of type `V` derived from the polynomial of `algorithm`, calling `build` to
+/// create it if it is not cached yet.
+///
+/// The value is leaked once cached. `build` runs without holding the lock, so it may itself ask
+/// the cache for other values; when two threads race, the value of the first one is kept.
+#[cfg(feature = "use_std")]
+pub fn cached<R, T, V, F>(algorithm: &Algorithm<R, T>, build: F) -> Cached<V>
+    where R: Reflect + 'static,
+          T: Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>,
+          V: Any + Send + Sync,
+          F: FnOnce() -> V
+{
+    let key = (TypeId::of::<V>(), TypeId::of::<R>(), algorithm.polynomial().into());
+
+    if let Some(value) = entries().read().unwrap_or_else(|e| e.into_inner()).get(&key) {
+        return value.downcast_ref().expect("cache entry of the wrong type");
+    }
+
+    let value = build();
+    let mut entries = entries().write().unwrap_or_else(|e| e.into_inner());
+    let value = *entries.entry(key).or_insert_with(|| Box::leak(Box::new(value)));
+    value.downcast_ref().expect("cache entry of the wrong type")
+}
+
+/// Returns the value of type `V` derived from the polynomial of `algorithm`, built by `build`.
+#[cfg(not(feature = "use_std"))]
+pub fn cached<R, T, V, F>(_: &Algorithm<R, T>, build: F) -> Cached<V>
+    where R: Reflect + 'static,
+          T: Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>,
+          V: Any + Send + Sync,
+          F: FnOnce() -> V
+{
+    build()
+}
+
+/// Returns the lookup table of type `B` for `algorithm`, building it from the table returned by
+/// `empty` if it is not cached yet.
+pub fn table<R, T, B, F>(algorithm: &Algorithm<R, T>, empty: F) -> Cached<B>
+    where R: Reflect + 'static,
+          T: Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>,
+          B: CrcTableBuilder<R, T> + Any + Send + Sync,
+          F: FnOnce() -> B
+{
+    cached(algorithm, || {
+        let mut table = empty();
+        table.build_table(algorithm);
+        table
+    })
+}
+
+#[cfg(all(test, feature = "use_std"))]
+mod tests {
+    use super::{cached, table};
+    use polynomial::algorithm::{CRC_16, CRC_32, CRC_32_C, CRC_32_MPEG_2};
+    use std::ptr;
+    use std::thread;
+
+    #[test]
+    fn shared() {
+        let first: &[u32; 256] = table(&CRC_32, || [0; 256]);
+        let second: &[u32; 256] = table(&CRC_32, || [0; 256]);
+        assert!(ptr::eq(first, second));
+    }
+
+    #[test]
+    fn keyed_by_parameters() {
+        let crc_32: &[u32; 256] = table(&CRC_32, || [0; 256]);
+        let crc_32_c: &[u32; 256] = table(&CRC_32_C, || [0; 256]);
+        let crc_32_mpeg_2: &[u32; 256] = table(&CRC_32_MPEG_2, || [0; 256]);
+        let slices: &[[u32; 256]; 4] = table(&CRC_32, || [[0; 256]; 4]);
+
+        assert!(crc_32[1] != crc_32_c[1]);
+        assert!(crc_32[1] != crc_32_mpeg_2[1]);
+        assert!(crc_32[..] == slices[0][..]);
+    }
+
+    #[test]
+    fn threads() {
+        let expected = table(&CRC_32_C, || [[0u32; 256]; 8]) as *const _ as usize;
+        let handles: Vec<_> = (0..4)
+            .map(|_| thread::spawn(|| table(&CRC_32_C, || [[0u32; 256]; 8]) as *const _ as usize))
+            .collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap() == expected);
+        }
+    }
+
+    #[test]
+    fn nested() {
+        let outer: &(u16, u16) = cached(&CRC_16, || {
+            let inner: &[u16; 256] = table(&CRC_16, || [0; 256]);
+            (inner[1], inner[2])
+        });
+        let inner: &[u16; 256] = table(&CRC_16, || [0; 256]);
+
+        assert!(*outer == (inner[1], inner[2]));
+    }
+}
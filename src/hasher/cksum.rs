@@ -0,0 +1,194 @@
+//! The checksum printed by the POSIX `cksum` utility.
+//!
+//! `cksum` hashes the message with CRC-32/CKSUM, then hashes the length of the message in bytes,
+//! least significant byte first and without the most significant zero bytes, and only then
+//! finalizes the CRC. The length makes messages that only differ by leading zero bytes, which
+//! have the same CRC when the initial value is zero, get different checksums.
+
+use Crc32;
+use polynomial::algorithm::{Reflect, No, Algorithm, CrcAlgorithm, CRC_32_CKSUM};
+use hasher::{CrcHasher, Digest};
+use hasher::multi::CrcDigest;
+
+/// Running checksum of a message hashing its length after it, as `cksum` does.
+///
+/// `Default` creates the checksum of `cksum` itself. Other algorithms can be used with `new`,
+/// in which case the length is appended the same way.
+pub struct Cksum<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    digest: Digest<'a, H, R, T>,
+    len: u64,
+}
+
+impl<'a, H, R, T> Cksum<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Creates a checksum computing `algorithm` with the hasher `H`.
+    pub fn new(algorithm: &'a Algorithm<R, T>) -> Self {
+        Cksum::with_hasher(H::with(algorithm))
+    }
+
+    /// Creates a checksum from an existing hasher.
+    pub fn with_hasher(hasher: H) -> Self {
+        Cksum {
+            digest: Digest::with_hasher(hasher),
+            len: 0,
+        }
+    }
+
+    /// Adds `bytes` to the message.
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.digest.update(bytes);
+        self.len += bytes.len() as u64;
+    }
+
+    /// Returns the length of the message so far.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if nothing was added to the message yet.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the checksum of the message so far. The checksum is left untouched and can still be
+    /// updated afterwards.
+    pub fn finalize(&self) -> T
+        where H: Clone
+    {
+        // The length goes to a fork of the digest so the message can go on afterwards.
+        let bytes = self.len.to_le_bytes();
+        let used = (64 - self.len.leading_zeros() as usize).div_ceil(8);
+        let mut digest = self.digest.clone();
+        digest.update(&bytes[..used]);
+        digest.finalize()
+    }
+
+    /// Starts a new message.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.digest.reset();
+        self.len = 0;
+    }
+}
+
+impl<H> Default for Cksum<'static, H, No, Crc32>
+    where H: CrcHasher<'static, No, Crc32>
+{
+    fn default() -> Self {
+        Cksum::new(&CRC_32_CKSUM)
+    }
+}
+
+impl<'a, H, R, T> Clone for Cksum<'a, H, R, T>
+    where H: CrcHasher<'a, R, T> + Clone,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn clone(&self) -> Self {
+        Cksum {
+            digest: self.digest.clone(),
+            len: self.len,
+        }
+    }
+}
+
+impl<'a, H, R, T> CrcDigest for Cksum<'a, H, R, T>
+    where H: CrcHasher<'a, R, T> + Clone,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    type Output = T;
+
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        Cksum::update(self, bytes);
+    }
+
+    #[inline]
+    fn finalize(&self) -> T {
+        Cksum::finalize(self)
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        Cksum::reset(self);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Cksum;
+    use hasher::{CrcHasher, Digest, Table, Slicex8, Bitwise};
+    use polynomial::algorithm::{CrcAlgorithm, CRC_32_CKSUM};
+    use std::vec::Vec;
+
+    // Outputs of `printf <message> | cksum`.
+    static VECTORS: &[(&[u8], u32)] = &[
+        (b"", 4294967295),
+        (b"a", 1220704766),
+        (b"123456789", 930766865),
+        (b"The quick brown fox jumps over the lazy dog", 2074844392),
+    ];
+
+    #[test]
+    fn check() {
+        let (bytes, check_val) = CRC_32_CKSUM.check();
+        let hasher = Table::with(&CRC_32_CKSUM);
+        assert!(check_val == hasher.finalize_crc(hasher.update_crc(0, bytes)));
+    }
+
+    #[test]
+    fn posix_vectors() {
+        for &(message, expected) in VECTORS {
+            let mut cksum: Cksum<Table<_, _>, _, _> = Cksum::default();
+            cksum.update(message);
+            assert!(cksum.finalize() == expected);
+            assert!(cksum.len() == message.len() as u64);
+        }
+    }
+
+    #[test]
+    fn long_message() {
+        // 256 bytes take two bytes of length, 65536 three.
+        for &len in &[255usize, 256, 65535, 65536] {
+            let bytes: Vec<u8> = (0..len as u32).map(|i| (i * 7 + i / 13) as u8).collect();
+            let mut cksum: Cksum<Slicex8<_, _>, _, _> = Cksum::default();
+            for piece in bytes.chunks(1000) {
+                cksum.update(piece);
+            }
+
+            let mut digest: Digest<Bitwise<_, _>, _, _> = Digest::new(&CRC_32_CKSUM);
+            digest.update(&bytes);
+            let mut len = len;
+            while len > 0 {
+                digest.update(&[len as u8]);
+                len >>= 8;
+            }
+            assert!(cksum.finalize() == digest.finalize());
+        }
+    }
+
+    #[test]
+    fn reset() {
+        let mut cksum: Cksum<Table<_, _>, _, _> = Cksum::default();
+        cksum.update(b"garbage");
+        cksum.reset();
+        assert!(cksum.is_empty());
+        cksum.update(b"123456789");
+        assert!(cksum.finalize() == 930766865);
+    }
+}
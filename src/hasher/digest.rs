@@ -0,0 +1,210 @@
+//! A stateful wrapper around the hashers.
+
+#[cfg(feature = "use_std")]
+use std::io::IoSlice;
+use core::marker::PhantomData;
+
+use polynomial::algorithm::{Reflect, ByteOrder, Algorithm, CrcAlgorithm};
+use hasher::CrcHasher;
+
+/// Running CRC of a message fed in pieces.
+///
+/// The digest owns the register so callers only `update` it with bytes and read the checksum
+/// with `finalize`. Cloning a digest forks it: both copies continue from the same state
+/// independently, which is handy to checksum several messages sharing a prefix.
+pub struct Digest<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    hasher: H,
+    crc: T,
+    algorithm: PhantomData<&'a Algorithm<R, T>>,
+}
+
+impl<'a, H, R, T> Digest<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Creates a digest computing `algorithm` with the hasher `H`.
+    pub fn new(algorithm: &'a Algorithm<R, T>) -> Self {
+        Digest::with_hasher(H::with(algorithm))
+    }
+
+    /// Creates a digest from an existing hasher.
+    pub fn with_hasher(hasher: H) -> Self {
+        let crc = hasher.initial_value();
+
+        Digest {
+            hasher,
+            crc,
+            algorithm: PhantomData,
+        }
+    }
+
+    /// Returns the byte order the checksum is serialized in, the one of the algorithm.
+    #[inline]
+    pub fn byte_order(&self) -> ByteOrder {
+        self.hasher.algorithm().byte_order()
+    }
+
+    /// Adds `bytes` to the message.
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.crc = self.hasher.update_crc(self.crc, bytes);
+    }
+
+    /// Adds each buffer of `chunks` to the message in turn.
+    #[inline]
+    pub fn update_chunks<'b, I>(&mut self, chunks: I)
+        where I: IntoIterator<Item = &'b [u8]>
+    {
+        self.crc = self.hasher.update_crc_chunks(self.crc, chunks);
+    }
+
+    /// Adds the buffers of a scatter-gather list to the message.
+    #[cfg(feature = "use_std")]
+    #[inline]
+    pub fn update_vectored(&mut self, bufs: &[IoSlice]) {
+        self.crc = self.hasher.update_crc_vectored(self.crc, bufs);
+    }
+
+    /// Returns the checksum of the message so far. The digest is left untouched and can still be
+    /// updated afterwards.
+    #[inline]
+    pub fn finalize(&self) -> T {
+        self.hasher.finalize_crc(self.crc)
+    }
+
+    /// Starts a new message.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.crc = self.hasher.initial_value();
+    }
+
+    /// Returns the hasher used by the digest.
+    #[inline]
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+}
+
+impl<'a, H, R, T> Clone for Digest<'a, H, R, T>
+    where H: CrcHasher<'a, R, T> + Clone,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn clone(&self) -> Self {
+        Digest {
+            hasher: self.hasher.clone(),
+            crc: self.crc,
+            algorithm: PhantomData,
+        }
+    }
+}
+
+macro_rules! test_digest {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::Digest;
+            use hasher::{CrcHasher, Bitwise, Table};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let mut digest: Digest<Table<_, _>, _, _> = Digest::new(&$poly);
+                digest.update(bytes);
+                assert!(check_val == digest.finalize());
+            }
+
+            #[test]
+            fn pieces() {
+                let (bytes, check_val) = $poly.check();
+                let mut digest = Digest::with_hasher(Bitwise::with(&$poly));
+
+                for piece in bytes.chunks(2) {
+                    digest.update(piece);
+                }
+                assert!(check_val == digest.finalize());
+
+                digest.update(&[]);
+                assert!(check_val == digest.finalize());
+            }
+
+            #[test]
+            fn chunks() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Table::with(&$poly);
+                let crc = hasher.update_crc_chunks(hasher.initial_value(), bytes.chunks(4));
+                assert!(check_val == hasher.finalize_crc(crc));
+
+                let mut digest = Digest::with_hasher(hasher);
+                digest.update_chunks(vec![&bytes[..1], &[], &bytes[1..]]);
+                assert!(check_val == digest.finalize());
+            }
+
+            #[cfg(feature = "use_std")]
+            #[test]
+            fn vectored() {
+                use std::io::IoSlice;
+
+                let (bytes, check_val) = $poly.check();
+                let bufs = [IoSlice::new(&bytes[..2]),
+                            IoSlice::new(&[]),
+                            IoSlice::new(&bytes[2..])];
+                let hasher = Table::with(&$poly);
+                let crc = hasher.update_crc_vectored(hasher.initial_value(), &bufs);
+                assert!(check_val == hasher.finalize_crc(crc));
+
+                let mut digest = Digest::with_hasher(hasher);
+                digest.update_vectored(&bufs);
+                assert!(check_val == digest.finalize());
+            }
+
+            #[test]
+            fn reset() {
+                let (bytes, check_val) = $poly.check();
+                let mut digest: Digest<Table<_, _>, _, _> = Digest::new(&$poly);
+                digest.update(b"garbage");
+                digest.reset();
+                digest.update(bytes);
+                assert!(check_val == digest.finalize());
+            }
+
+            #[test]
+            fn fork() {
+                let (bytes, check_val) = $poly.check();
+                let mut digest: Digest<Table<_, _>, _, _> = Digest::new(&$poly);
+                digest.update(&bytes[..4]);
+
+                let mut fork = digest.clone();
+                fork.update(&bytes[4..]);
+                digest.update(b"56789");
+
+                assert!(check_val == fork.finalize());
+                assert!(check_val == digest.finalize());
+
+                digest.update(b"0");
+                assert!(check_val == fork.finalize());
+            }
+        }
+    )*)
+}
+
+test_digest!(CRC_8,
+             CRC_8_MAXIM,
+             CRC_16,
+             CRC_16_XMODEM,
+             CRC_32,
+             CRC_32_C,
+             CRC_32_MPEG_2,
+             CRC_32_Q,
+             CRC_64,
+             CRC_64_XZ);
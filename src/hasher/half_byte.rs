@@ -0,0 +1,92 @@
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Yes, No, Algorithm, CrcAlgorithm};
+use hasher::nibble::{NibbleHasher, NibbleTable};
+
+
+/// Hasher processing a byte at a time with two 16 entry tables, one for each half of the byte.
+/// It is noticeably faster than `Nibble` for twice the memory.
+pub type HalfByte<'a, R, T> = NibbleHasher<'a, R, T, [[T; 16]; 2]>;
+
+macro_rules! doit {
+    ($($ty:ty),*) => ($(
+        impl NibbleTable<Yes, $ty> for [[$ty; 16]; 2]
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            #[inline]
+            fn zeroed() -> Self {
+                [[0; 16]; 2]
+            }
+
+            #[inline(always)]
+            fn step(&self, crc: $ty, byte: u8) -> $ty {
+                let index = (crc as u8) ^ byte;
+                (((crc as u64) >> 8) as $ty) ^ self[0][(index & 0xF) as usize] ^
+                self[1][(index >> 4) as usize]
+            }
+        }
+
+        impl NibbleTable<No, $ty> for [[$ty; 16]; 2]
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            #[inline]
+            fn zeroed() -> Self {
+                [[0; 16]; 2]
+            }
+
+            #[inline(always)]
+            fn step(&self, crc: $ty, byte: u8) -> $ty {
+                let index = (crc.rotate_left(8) as u8) ^ byte;
+                (((crc as u64) << 8) as $ty) ^ self[0][(index & 0xF) as usize] ^
+                self[1][(index >> 4) as usize]
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, Crc16, Crc32, Crc64);
+
+macro_rules! test_half_byte_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::HalfByte;
+            use hasher::CrcHasher;
+            use hasher::bitwise::Bitwise;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = HalfByte::with(&$poly);
+                let mut crc = hasher.initial_value();
+                crc = hasher.update_crc(crc, bytes);
+                crc = hasher.finalize_crc(crc);
+                assert!(check_val == crc);
+            }
+
+            #[test]
+            fn every_byte() {
+                let bitwise = Bitwise::with(&$poly);
+                let hasher = HalfByte::with(&$poly);
+
+                for byte in 0..256 {
+                    let bytes = [byte as u8, !byte as u8];
+                    let crc = hasher.update_crc(hasher.initial_value(), &bytes);
+                    assert!(bitwise.update_crc(bitwise.initial_value(), &bytes) == crc);
+                }
+            }
+        }
+    )*)
+}
+
+test_half_byte_hasher!(CRC_8,
+                       CRC_8_MAXIM,
+                       CRC_16,
+                       CRC_16_XMODEM,
+                       CRC_32,
+                       CRC_32_C,
+                       CRC_32_MPEG_2,
+                       CRC_32_Q,
+                       CRC_64,
+                       CRC_64_XZ);
@@ -0,0 +1,206 @@
+//! Adapters to use the CRCs as the hash function of the standard collections.
+//!
+//! A CRC is not a keyed hash and gives no protection against collisions crafted on purpose, so
+//! it should only be used for maps whose keys are trusted.
+//!
+//! The collections take some of their bits from the top of the hash, where a CRC narrower than
+//! 64 bits only has zeros, so the hashers spread the CRC over the whole `u64` before returning
+//! it.
+
+use std::hash::{Hasher, BuildHasher};
+
+use {Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, Algorithm, CrcAlgorithm, CRC_32_C, CRC_64_XZ};
+use hasher::{CrcHasher, Digest, Sse42, Auto};
+
+/// Odd constant the CRC is multiplied by, which carries every bit of it into the top bits
+/// without mapping two CRCs to the same hash.
+const SPREAD: u64 = 0x9e3779b97f4a7c15;
+
+/// `Hasher` computing the CRC of the bytes written, as created by `BuildCrcHasher`.
+pub struct StdHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    digest: Digest<'a, H, R, T>,
+}
+
+impl<'a, H, R, T> StdHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Returns the CRC of the bytes written so far, before it is spread by `finish`.
+    #[inline]
+    pub fn crc(&self) -> T {
+        self.digest.finalize()
+    }
+}
+
+impl<'a, H, R, T> Clone for StdHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T> + Clone,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn clone(&self) -> Self {
+        StdHasher { digest: self.digest.clone() }
+    }
+}
+
+impl<'a, H, R, T> Hasher for StdHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy + Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.digest.update(bytes);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.crc().into().wrapping_mul(SPREAD)
+    }
+}
+
+/// Creates a `StdHasher` for every key hashed, by cloning the hasher it was built with so nothing
+/// is set up again per key.
+pub struct BuildCrcHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    digest: Digest<'a, H, R, T>,
+}
+
+impl<'a, H, R, T> BuildCrcHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Creates a builder hashing with `algorithm` and the hasher `H`.
+    pub fn new(algorithm: &'a Algorithm<R, T>) -> Self {
+        BuildCrcHasher { digest: Digest::new(algorithm) }
+    }
+}
+
+impl<'a, H, R, T> Clone for BuildCrcHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T> + Clone,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn clone(&self) -> Self {
+        BuildCrcHasher { digest: self.digest.clone() }
+    }
+}
+
+impl<'a, H, R, T> BuildHasher for BuildCrcHasher<'a, H, R, T>
+    where H: CrcHasher<'a, R, T> + Clone,
+          R: 'a + Reflect,
+          T: 'a + Copy + Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    type Hasher = StdHasher<'a, H, R, T>;
+
+    #[inline]
+    fn build_hasher(&self) -> Self::Hasher {
+        StdHasher { digest: self.digest.clone() }
+    }
+}
+
+/// Builds CRC-32C hashers, using the `crc32` instruction when the CPU has it.
+pub type BuildCrc32C = BuildCrcHasher<'static, Sse42<'static, Yes, Crc32>, Yes, Crc32>;
+
+/// Builds CRC-64/XZ hashers.
+pub type BuildCrc64Xz = BuildCrcHasher<'static, Auto<'static, Yes, Crc64>, Yes, Crc64>;
+
+impl Default for BuildCrc32C {
+    fn default() -> Self {
+        BuildCrcHasher::new(&CRC_32_C)
+    }
+}
+
+impl Default for BuildCrc64Xz {
+    fn default() -> Self {
+        BuildCrcHasher::new(&CRC_64_XZ)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BuildCrcHasher, BuildCrc32C, BuildCrc64Xz, SPREAD};
+    use hasher::Table;
+    use polynomial::algorithm::{CrcAlgorithm, CRC_16, CRC_32_C, CRC_64_XZ};
+    use std::collections::HashMap;
+    use std::hash::{Hasher, BuildHasher};
+
+    #[test]
+    fn finish() {
+        let (bytes, check_val) = CRC_32_C.check();
+        let mut hasher = BuildCrc32C::default().build_hasher();
+        hasher.write(bytes);
+        assert!(check_val == hasher.crc());
+        assert!((check_val as u64).wrapping_mul(SPREAD) == hasher.finish());
+
+        let (bytes, check_val) = CRC_64_XZ.check();
+        let mut hasher = BuildCrc64Xz::default().build_hasher();
+        hasher.write(&bytes[..3]);
+        hasher.write(&bytes[3..]);
+        assert!(check_val == hasher.crc());
+        assert!(check_val.wrapping_mul(SPREAD) == hasher.finish());
+
+        let (bytes, check_val) = CRC_16.check();
+        let mut hasher = BuildCrcHasher::<Table<_, _>, _, _>::new(&CRC_16).build_hasher();
+        hasher.write(bytes);
+        assert!(check_val == hasher.crc());
+    }
+
+    #[test]
+    fn top_bits() {
+        let build = BuildCrc32C::default();
+        let tops: Vec<u64> = (0..64u32)
+            .map(|i| {
+                let mut hasher = build.build_hasher();
+                hasher.write(&i.to_le_bytes());
+                hasher.finish() >> 57
+            })
+            .collect();
+
+        assert!(tops.iter().any(|&top| top != tops[0]));
+    }
+
+    #[test]
+    fn independent() {
+        let build = BuildCrc32C::default();
+        let mut first = build.build_hasher();
+        first.write(b"first");
+        let second = build.build_hasher();
+        assert!(second.finish() == build.build_hasher().finish());
+        assert!(first.finish() != second.finish());
+    }
+
+    #[test]
+    fn hash_map() {
+        let mut map: HashMap<Vec<u8>, usize, BuildCrc32C> = HashMap::default();
+
+        for i in 0..1000usize {
+            map.insert(i.to_string().into_bytes(), i);
+        }
+
+        for i in 0..1000usize {
+            assert!(map[i.to_string().as_bytes()] == i);
+        }
+
+        let mut map: HashMap<&[u8], usize, BuildCrc64Xz> = HashMap::default();
+        map.insert(b"key", 1);
+        assert!(map.get(&b"key"[..]) == Some(&1));
+    }
+}
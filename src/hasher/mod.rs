@@ -1,19 +1,85 @@
-use polynomial::{Reflect, CrcPoly, CrcPolynomial};
+#[cfg(feature = "use_std")]
+use std::io::IoSlice;
+
+use polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
 
 pub mod bitwise;
 pub mod table_builder;
+pub mod cache;
 pub mod table;
-//pub mod slicex4;
+pub mod nibble;
+pub mod half_byte;
+pub mod slicex4;
+pub mod slicex8;
+pub mod slicex16;
+pub mod sse42;
+pub mod pclmul;
+pub mod simd;
+pub mod auto;
+pub mod owned;
+pub mod digest;
+pub mod multi;
+pub mod cksum;
+#[cfg(feature = "use_std")]
+pub mod hash;
+#[cfg(feature = "digest")]
+mod rust_crypto;
 
 pub use self::bitwise::Bitwise;
 pub use self::table::Table;
+pub use self::nibble::Nibble;
+pub use self::half_byte::HalfByte;
+pub use self::slicex4::Slicex4;
+pub use self::slicex8::Slicex8;
+pub use self::slicex16::Slicex16;
+pub use self::sse42::Sse42;
+pub use self::pclmul::Pclmul;
+pub use self::simd::Simd;
+pub use self::auto::Auto;
+pub use self::owned::{OwnedBitwise, OwnedTable};
+pub use self::digest::Digest;
+pub use self::multi::{CrcDigest, MultiDigest};
+pub use self::cksum::Cksum;
+#[cfg(feature = "use_std")]
+pub use self::hash::{StdHasher, BuildCrcHasher, BuildCrc32C, BuildCrc64Xz};
 
 pub trait CrcHasher<'a, R, T>
     where R: Reflect,
-    CrcPoly<R, T>: CrcPolynomial<T>
+    Algorithm<R, T>: CrcAlgorithm<T>
 {
-    fn with(polynomial: &'a CrcPoly<R, T>) -> Self;
+    fn with(algorithm: &'a Algorithm<R, T>) -> Self;
     fn initial_value(&self) -> T;
     fn update_crc(&self, crc: T, bytes: &[u8]) -> T;
     fn finalize_crc(&self, crc: T) -> T;
+
+    /// Returns the algorithm the hasher computes, whose parameters such as the byte order of the
+    /// checksum apply to everything built on the hasher.
+    fn algorithm(&self) -> &Algorithm<R, T>;
+
+    /// Updates `crc` with each buffer of `chunks` in turn, as if they were contiguous.
+    fn update_crc_chunks<'b, I>(&self, crc: T, chunks: I) -> T
+        where I: IntoIterator<Item = &'b [u8]>
+    {
+        chunks.into_iter().fold(crc, |crc, chunk| self.update_crc(crc, chunk))
+    }
+
+    /// Updates `crc` with the buffers of a scatter-gather list, as if they were contiguous.
+    #[cfg(feature = "use_std")]
+    fn update_crc_vectored(&self, crc: T, bufs: &[IoSlice]) -> T {
+        self.update_crc_chunks(crc, bufs.iter().map(|buf| &buf[..]))
+    }
+
+    /// Updates each CRC of `crcs` with the buffer of `buffers` at the same index.
+    ///
+    /// Hashers that can work on several independent buffers at once override this to interleave
+    /// them. Panics if the slices have different lengths.
+    fn update_crc_many(&self, crcs: &mut [T], buffers: &[&[u8]])
+        where T: Copy
+    {
+        assert!(crcs.len() == buffers.len(), "one CRC is needed per buffer");
+
+        for (crc, bytes) in crcs.iter_mut().zip(buffers) {
+            *crc = self.update_crc(*crc, bytes);
+        }
+    }
 }
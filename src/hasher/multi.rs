@@ -0,0 +1,166 @@
+//! Several algorithms computed over the same message in a single pass.
+//!
+//! Feeding a large message to one digest after the other reads it from memory once per
+//! algorithm. `MultiDigest` instead splits the message in blocks of `BLOCK_LEN` bytes and hands
+//! every block to each digest in turn while it is still in the cache.
+
+use polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
+use hasher::{CrcHasher, Digest};
+
+/// The number of bytes handed to every digest in turn, small enough to stay in the L1 cache.
+pub const BLOCK_LEN: usize = 4096;
+
+/// A running checksum, implemented by `Digest` and by tuples of checksums.
+pub trait CrcDigest {
+    type Output;
+
+    fn update(&mut self, bytes: &[u8]);
+    fn finalize(&self) -> Self::Output;
+    fn reset(&mut self);
+}
+
+impl<'a, H, R, T> CrcDigest for Digest<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    type Output = T;
+
+    #[inline]
+    fn update(&mut self, bytes: &[u8]) {
+        Digest::update(self, bytes);
+    }
+
+    #[inline]
+    fn finalize(&self) -> T {
+        Digest::finalize(self)
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        Digest::reset(self);
+    }
+}
+
+macro_rules! doit {
+    ($(($($name:ident . $idx:tt),*));*) => ($(
+        impl<$($name),*> CrcDigest for ($($name,)*)
+            where $($name: CrcDigest),*
+        {
+            type Output = ($($name::Output,)*);
+
+            #[inline]
+            fn update(&mut self, bytes: &[u8]) {
+                $(self.$idx.update(bytes);)*
+            }
+
+            #[inline]
+            fn finalize(&self) -> Self::Output {
+                ($(self.$idx.finalize(),)*)
+            }
+
+            #[inline]
+            fn reset(&mut self) {
+                $(self.$idx.reset();)*
+            }
+        }
+    )*)
+}
+
+doit!((A.0, B.1);
+      (A.0, B.1, C.2);
+      (A.0, B.1, C.2, D.3);
+      (A.0, B.1, C.2, D.3, E.4);
+      (A.0, B.1, C.2, D.3, E.4, F.5));
+
+/// Checksums of a message for several algorithms, possibly of different widths.
+///
+/// The digests are usually given as a tuple such as `(Digest<Table<..>, ..>, Digest<Sse42<..>,
+/// ..>)`, and `finalize` returns a tuple of their checksums in the same order.
+#[derive(Clone)]
+pub struct MultiDigest<D> {
+    digests: D,
+}
+
+impl<D> MultiDigest<D>
+    where D: CrcDigest
+{
+    /// Creates a digest feeding every message to `digests`.
+    pub fn new(digests: D) -> Self {
+        MultiDigest { digests }
+    }
+
+    /// Adds `bytes` to the message of every digest, one block at a time.
+    pub fn update(&mut self, bytes: &[u8]) {
+        for block in bytes.chunks(BLOCK_LEN) {
+            self.digests.update(block);
+        }
+    }
+
+    /// Returns the checksums of the message so far.
+    #[inline]
+    pub fn finalize(&self) -> D::Output {
+        self.digests.finalize()
+    }
+
+    /// Starts a new message for every digest.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.digests.reset();
+    }
+
+    /// Returns the digests.
+    #[inline]
+    pub fn digests(&self) -> &D {
+        &self.digests
+    }
+
+    /// Returns the digests, consuming the multi-digest.
+    #[inline]
+    pub fn into_inner(self) -> D {
+        self.digests
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MultiDigest, BLOCK_LEN};
+    use hasher::{Digest, Table, Slicex8, Auto};
+    use polynomial::algorithm::{CrcAlgorithm, CRC_8, CRC_16, CRC_32, CRC_32_C, CRC_64_XZ};
+    use std::vec::Vec;
+
+    #[test]
+    fn check() {
+        let mut digest = MultiDigest::new((Digest::<Table<_, _>, _, _>::new(&CRC_32),
+                                           Digest::<Slicex8<_, _>, _, _>::new(&CRC_32_C)));
+        let (bytes, _) = CRC_32.check();
+        digest.update(bytes);
+        assert!(digest.finalize() == (CRC_32.check().1, CRC_32_C.check().1));
+
+        digest.reset();
+        digest.update(&bytes[..4]);
+        digest.update(&bytes[4..]);
+        assert!(digest.finalize() == (CRC_32.check().1, CRC_32_C.check().1));
+    }
+
+    #[test]
+    fn mixed_widths() {
+        let bytes: Vec<u8> = (0..3 * BLOCK_LEN as u32 + 100).map(|i| (i * 13 + i / 7) as u8)
+            .collect();
+        let mut crc_8: Digest<Table<_, _>, _, _> = Digest::new(&CRC_8);
+        let mut crc_16: Digest<Table<_, _>, _, _> = Digest::new(&CRC_16);
+        let mut crc_64_xz: Digest<Auto<_, _>, _, _> = Digest::new(&CRC_64_XZ);
+        let mut digest = MultiDigest::new((crc_8.clone(), crc_16.clone(), crc_64_xz.clone()));
+
+        for piece in bytes.chunks(BLOCK_LEN - 1) {
+            digest.update(piece);
+        }
+        crc_8.update(&bytes);
+        crc_16.update(&bytes);
+        crc_64_xz.update(&bytes);
+
+        assert!(digest.finalize() == (crc_8.finalize(), crc_16.finalize(), crc_64_xz.finalize()));
+        assert!(digest.into_inner().1.finalize() == crc_16.finalize());
+    }
+}
@@ -0,0 +1,157 @@
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use hasher::CrcHasher;
+use hasher::table_builder::CrcTableBuilder;
+
+
+/// Hasher running each byte through a table of nibbles. `Nibble` and `HalfByte` only differ in
+/// their table, which decides how much of the byte a lookup covers.
+#[derive(Clone)]
+pub struct NibbleHasher<'a, R, T, N>
+    where R: 'a + Reflect,
+          T: 'a
+{
+    algorithm: &'a Algorithm<R, T>,
+    table: N,
+}
+
+/// Hasher processing four bits at a time with a 16 entry table, for targets where the 256 entry
+/// table of `Table` does not fit.
+pub type Nibble<'a, R, T> = NibbleHasher<'a, R, T, [T; 16]>;
+
+/// A table of nibbles and the step it takes over a byte.
+pub trait NibbleTable<R, T>: CrcTableBuilder<R, T>
+    where R: Reflect,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Returns a table to build, with every entry zero.
+    fn zeroed() -> Self;
+
+    /// Advances `crc` over `byte`.
+    fn step(&self, crc: T, byte: u8) -> T;
+}
+
+impl<'a, R, T, N> CrcHasher<'a, R, T> for NibbleHasher<'a, R, T, N>
+    where R: Reflect,
+          Algorithm<R, T>: CrcAlgorithm<T>,
+          N: NibbleTable<R, T>
+{
+    fn with(algorithm: &'a Algorithm<R, T>) -> Self {
+        let mut hasher = NibbleHasher {
+            algorithm,
+            table: N::zeroed(),
+        };
+
+        hasher.table.build_table(algorithm);
+        hasher
+    }
+
+    #[inline]
+    fn algorithm(&self) -> &Algorithm<R, T> {
+        self.algorithm
+    }
+
+    #[inline]
+    fn initial_value(&self) -> T {
+        self.algorithm.initial()
+    }
+
+    fn update_crc(&self, crc: T, bytes: &[u8]) -> T {
+        let mut crc = crc;
+
+        for &byte in bytes {
+            crc = self.table.step(crc, byte);
+        }
+
+        crc
+    }
+
+    #[inline]
+    fn finalize_crc(&self, crc: T) -> T {
+        self.algorithm.finalize_crc(crc)
+    }
+}
+
+macro_rules! doit {
+    ($($ty:ty),*) => ($(
+        impl NibbleTable<Yes, $ty> for [$ty; 16]
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            #[inline]
+            fn zeroed() -> Self {
+                [0; 16]
+            }
+
+            #[inline(always)]
+            fn step(&self, crc: $ty, byte: u8) -> $ty {
+                let crc = crc ^ byte as $ty;
+                let crc = (crc >> 4) ^ self[(crc & 0xF) as usize];
+                (crc >> 4) ^ self[(crc & 0xF) as usize]
+            }
+        }
+
+        impl NibbleTable<No, $ty> for [$ty; 16]
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            #[inline]
+            fn zeroed() -> Self {
+                [0; 16]
+            }
+
+            #[inline(always)]
+            fn step(&self, crc: $ty, byte: u8) -> $ty {
+                let crc = crc ^ (byte as $ty).rotate_right(8);
+                let crc = (crc << 4) ^ self[(crc.rotate_left(4) & 0xF) as usize];
+                (crc << 4) ^ self[(crc.rotate_left(4) & 0xF) as usize]
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, Crc16, Crc32, Crc64);
+
+macro_rules! test_nibble_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::Nibble;
+            use hasher::CrcHasher;
+            use hasher::bitwise::Bitwise;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Nibble::with(&$poly);
+                let mut crc = hasher.initial_value();
+                crc = hasher.update_crc(crc, bytes);
+                crc = hasher.finalize_crc(crc);
+                assert!(check_val == crc);
+            }
+
+            #[test]
+            fn every_byte() {
+                let bitwise = Bitwise::with(&$poly);
+                let hasher = Nibble::with(&$poly);
+
+                for byte in 0..256 {
+                    let bytes = [byte as u8, !byte as u8];
+                    let crc = hasher.update_crc(hasher.initial_value(), &bytes);
+                    assert!(bitwise.update_crc(bitwise.initial_value(), &bytes) == crc);
+                }
+            }
+        }
+    )*)
+}
+
+test_nibble_hasher!(CRC_8,
+                    CRC_8_MAXIM,
+                    CRC_16,
+                    CRC_16_XMODEM,
+                    CRC_32,
+                    CRC_32_C,
+                    CRC_32_MPEG_2,
+                    CRC_32_Q,
+                    CRC_64,
+                    CRC_64_XZ);
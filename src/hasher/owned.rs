@@ -0,0 +1,224 @@
+//! Hashers that own their algorithm instead of borrowing it.
+//!
+//! They can be stored in long-lived structs, built from an algorithm created at runtime and
+//! moved or shared across threads, at the cost of copying the algorithm parameters.
+
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use hasher::{CrcHasher, Bitwise};
+use hasher::cache;
+
+/// `Bitwise` hasher owning a copy of its algorithm.
+#[derive(Debug, Clone, Copy)]
+pub struct OwnedBitwise<R, T>
+    where R: Reflect
+{
+    algorithm: Algorithm<R, T>,
+}
+
+/// `Table` hasher owning a copy of its algorithm.
+#[derive(Clone)]
+pub struct OwnedTable<R, T>
+    where R: Reflect,
+          T: 'static
+{
+    algorithm: Algorithm<R, T>,
+    table: cache::Cached<[T; 256]>,
+}
+
+macro_rules! doit {
+    ($($ty:ty),*) => ($(
+        impl<'a> CrcHasher<'a, Yes, $ty> for OwnedBitwise<Yes, $ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            #[inline]
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                OwnedBitwise {
+                    algorithm: *algorithm,
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                &self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            #[inline]
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                Bitwise::with(&self.algorithm).update_crc(crc, bytes)
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+
+        impl<'a> CrcHasher<'a, No, $ty> for OwnedBitwise<No, $ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            #[inline]
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                OwnedBitwise {
+                    algorithm: *algorithm,
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                &self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            #[inline]
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                Bitwise::with(&self.algorithm).update_crc(crc, bytes)
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+
+        impl<'a> CrcHasher<'a, Yes, $ty> for OwnedTable<Yes, $ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                OwnedTable {
+                    algorithm: *algorithm,
+                    table: cache::table(algorithm, || [0; 256]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                &self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+
+                for &byte in bytes {
+                    crc = (((crc as u64) >> 8) as $ty) ^
+                          self.table[((crc as u8) ^ byte) as usize];
+                }
+
+                crc
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+
+        impl<'a> CrcHasher<'a, No, $ty> for OwnedTable<No, $ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                OwnedTable {
+                    algorithm: *algorithm,
+                    table: cache::table(algorithm, || [0; 256]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                &self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+
+                for &byte in bytes {
+                    crc = (((crc as u64) << 8) as $ty) ^
+                          self.table[((crc.rotate_left(8) as u8) ^ byte) as usize];
+                }
+
+                crc
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, Crc16, Crc32, Crc64);
+
+macro_rules! test_owned_hashers {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::{OwnedBitwise, OwnedTable};
+            use hasher::CrcHasher;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::thread;
+
+            fn assert_send_sync_clone<H: Clone + Send + Sync>(_: &H) {}
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+
+                let hasher = OwnedBitwise::with(&$poly);
+                assert_send_sync_clone(&hasher);
+                let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                assert!(check_val == hasher.finalize_crc(crc));
+
+                let hasher = OwnedTable::with(&$poly);
+                assert_send_sync_clone(&hasher);
+                let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                assert!(check_val == hasher.finalize_crc(crc));
+            }
+
+            #[test]
+            fn outlives_algorithm() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = {
+                    let algorithm = $poly;
+                    OwnedTable::with(&algorithm)
+                };
+
+                let crc = thread::spawn(move || {
+                    hasher.finalize_crc(hasher.update_crc(hasher.initial_value(), bytes))
+                }).join().unwrap();
+                assert!(check_val == crc);
+            }
+        }
+    )*)
+}
+
+test_owned_hashers!(CRC_8,
+                    CRC_8_MAXIM,
+                    CRC_16,
+                    CRC_16_XMODEM,
+                    CRC_32,
+                    CRC_32_C,
+                    CRC_32_MPEG_2,
+                    CRC_32_Q,
+                    CRC_64,
+                    CRC_64_XZ);
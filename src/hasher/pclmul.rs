@@ -0,0 +1,368 @@
+//! Carry-less multiplication folding as described in Intel's "Fast CRC Computation for Generic
+//! Polynomials Using PCLMULQDQ Instruction".
+//!
+//! The message is reduced 128 bits at a time by multiplying the accumulated value with
+//! x^n mod P for the distance n it has to move, which only needs the value to stay congruent to
+//! the message modulo the polynomial. The folded 16 bytes and any trailing bytes are then run
+//! through the lookup table, which replaces the Barrett reduction of the paper and lets the same
+//! constants work for every width from 8 to 64 bits.
+
+use core::marker::PhantomData;
+
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use polynomial::combine::CrcCombine;
+use hasher::{CrcHasher, Table};
+use hasher::cache;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// Buffers shorter than this are cheaper to hash with the table alone.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+const MIN_LEN: usize = 64;
+
+/// The folding constants of an algorithm with a CRC of type `T`, as the low and high 64 bit
+/// lanes of the multiplier.
+#[derive(Clone)]
+#[cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(dead_code))]
+struct Keys<T> {
+    by_1: [u64; 2],
+    by_4: [u64; 2],
+    // Keeps the constants of algorithms of different widths apart in the cache.
+    width: PhantomData<T>,
+}
+
+/// Hasher folding the message with PCLMULQDQ when the CPU supports it and using a lookup table
+/// otherwise.
+#[derive(Clone)]
+pub struct Pclmul<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    algorithm: &'a Algorithm<R, T>,
+    table: Table<'a, R, T>,
+    hardware: bool,
+    #[cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(dead_code))]
+    keys: cache::Cached<Keys<T>>,
+}
+
+impl<'a, R, T> Pclmul<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    /// Returns true when the hardware path is used by this hasher.
+    #[inline]
+    pub fn is_hardware(&self) -> bool {
+        self.hardware
+    }
+}
+
+macro_rules! doit {
+    ($($ty:ty, $bits:expr);*) => ($(
+        impl<'a> CrcHasher<'a, Yes, $ty> for Pclmul<'a, Yes, $ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                // A reflected product comes out one bit short, so every constant carries one
+                // power of x less than the distance it folds over.
+                let key = |n: u64| (algorithm.x_pow(n - 1) as u64) << (64 - $bits);
+
+                Pclmul {
+                    algorithm,
+                    table: Table::with(algorithm),
+                    hardware: has_pclmulqdq(),
+                    keys: cache::cached(algorithm, || {
+                        Keys {
+                            by_1: [key(128 + 64), key(128)],
+                            by_4: [key(512 + 64), key(512)],
+                            width: PhantomData,
+                        }
+                    }),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                {
+                    if self.hardware && bytes.len() >= MIN_LEN {
+                        let (folded, rest) = unsafe {
+                            fold_reflected(crc as u64, bytes, &self.keys)
+                        };
+                        let crc = self.table.update_crc(0, &folded);
+                        return self.table.update_crc(crc, rest);
+                    }
+                }
+
+                self.table.update_crc(crc, bytes)
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+
+        impl<'a> CrcHasher<'a, No, $ty> for Pclmul<'a, No, $ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                let key = |n: u64| algorithm.x_pow(n) as u64;
+
+                Pclmul {
+                    algorithm,
+                    table: Table::with(algorithm),
+                    hardware: has_pclmulqdq(),
+                    keys: cache::cached(algorithm, || {
+                        Keys {
+                            by_1: [key(128), key(128 + 64)],
+                            by_4: [key(512), key(512 + 64)],
+                            width: PhantomData,
+                        }
+                    }),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                {
+                    if self.hardware && bytes.len() >= MIN_LEN {
+                        let (folded, rest) = unsafe {
+                            fold_normal((crc as u64) << (64 - $bits), bytes, &self.keys)
+                        };
+                        let crc = self.table.update_crc(0, &folded);
+                        return self.table.update_crc(crc, rest);
+                    }
+                }
+
+                self.table.update_crc(crc, bytes)
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, 8; Crc16, 16; Crc32, 32; Crc64, 64);
+
+/// Returns true when the CPU can fold with PCLMULQDQ.
+#[cfg(all(feature = "use_std", any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+pub fn has_pclmulqdq() -> bool {
+    is_x86_feature_detected!("pclmulqdq") && is_x86_feature_detected!("ssse3")
+}
+
+// Without std the features can only be known at compile time.
+/// Returns true when the CPU can fold with PCLMULQDQ.
+#[cfg(all(not(feature = "use_std"), any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+pub fn has_pclmulqdq() -> bool {
+    cfg!(target_feature = "pclmulqdq") && cfg!(target_feature = "ssse3")
+}
+
+/// Returns true when the CPU can fold with PCLMULQDQ.
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+pub fn has_pclmulqdq() -> bool {
+    false
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "pclmulqdq,sse2")]
+unsafe fn fold(value: __m128i, keys: __m128i, data: __m128i) -> __m128i {
+    _mm_xor_si128(_mm_xor_si128(_mm_clmulepi64_si128(value, keys, 0x00),
+                                _mm_clmulepi64_si128(value, keys, 0x11)),
+                  data)
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline(always)]
+unsafe fn load(bytes: &[u8]) -> __m128i {
+    let bytes = &bytes[..16];
+    _mm_loadu_si128(bytes.as_ptr() as *const __m128i)
+}
+
+/// Folds all of the 16 byte blocks of a message for a reflected algorithm, where the bytes are
+/// read in little endian order. Returns the folded block and the bytes that are left over.
+///
+/// `bytes` must be at least 64 bytes long.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "pclmulqdq,sse2")]
+unsafe fn fold_reflected<'b, T>(crc: u64, bytes: &'b [u8],
+                                keys: &Keys<T>) -> ([u8; 16], &'b [u8]) {
+    let by_1 = _mm_set_epi64x(keys.by_1[1] as i64, keys.by_1[0] as i64);
+    let by_4 = _mm_set_epi64x(keys.by_4[1] as i64, keys.by_4[0] as i64);
+
+    let mut x0 = _mm_xor_si128(load(bytes), _mm_set_epi64x(0, crc as i64));
+    let mut x1 = load(&bytes[16..]);
+    let mut x2 = load(&bytes[32..]);
+    let mut x3 = load(&bytes[48..]);
+    let mut bytes = &bytes[64..];
+
+    while bytes.len() >= 64 {
+        x0 = fold(x0, by_4, load(bytes));
+        x1 = fold(x1, by_4, load(&bytes[16..]));
+        x2 = fold(x2, by_4, load(&bytes[32..]));
+        x3 = fold(x3, by_4, load(&bytes[48..]));
+        bytes = &bytes[64..];
+    }
+
+    let mut x = fold(fold(fold(x0, by_1, x1), by_1, x2), by_1, x3);
+
+    while bytes.len() >= 16 {
+        x = fold(x, by_1, load(bytes));
+        bytes = &bytes[16..];
+    }
+
+    let mut folded = [0u8; 16];
+    _mm_storeu_si128(folded.as_mut_ptr() as *mut __m128i, x);
+    (folded, bytes)
+}
+
+/// Folds all of the 16 byte blocks of a message for a non-reflected algorithm, where the bytes
+/// are read in big endian order. `crc` is the register aligned to the top of a u64. Returns the
+/// folded block and the bytes that are left over.
+///
+/// `bytes` must be at least 64 bytes long.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "pclmulqdq,sse2,ssse3")]
+unsafe fn fold_normal<'b, T>(crc: u64, bytes: &'b [u8],
+                             keys: &Keys<T>) -> ([u8; 16], &'b [u8]) {
+    let by_1 = _mm_set_epi64x(keys.by_1[1] as i64, keys.by_1[0] as i64);
+    let by_4 = _mm_set_epi64x(keys.by_4[1] as i64, keys.by_4[0] as i64);
+    let swap = _mm_set_epi8(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15);
+    let load = |bytes: &[u8]| _mm_shuffle_epi8(load(bytes), swap);
+
+    let mut x0 = _mm_xor_si128(load(bytes), _mm_set_epi64x(crc as i64, 0));
+    let mut x1 = load(&bytes[16..]);
+    let mut x2 = load(&bytes[32..]);
+    let mut x3 = load(&bytes[48..]);
+    let mut bytes = &bytes[64..];
+
+    while bytes.len() >= 64 {
+        x0 = fold(x0, by_4, load(bytes));
+        x1 = fold(x1, by_4, load(&bytes[16..]));
+        x2 = fold(x2, by_4, load(&bytes[32..]));
+        x3 = fold(x3, by_4, load(&bytes[48..]));
+        bytes = &bytes[64..];
+    }
+
+    let mut x = fold(fold(fold(x0, by_1, x1), by_1, x2), by_1, x3);
+
+    while bytes.len() >= 16 {
+        x = fold(x, by_1, load(bytes));
+        bytes = &bytes[16..];
+    }
+
+    let mut folded = [0u8; 16];
+    _mm_storeu_si128(folded.as_mut_ptr() as *mut __m128i, _mm_shuffle_epi8(x, swap));
+    (folded, bytes)
+}
+
+macro_rules! test_pclmul_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::Pclmul;
+            use hasher::{CrcHasher, Table};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Pclmul::with(&$poly);
+                let mut crc = hasher.initial_value();
+                crc = hasher.update_crc(crc, bytes);
+                crc = hasher.finalize_crc(crc);
+                assert!(check_val == crc);
+            }
+
+            #[test]
+            fn large() {
+                let bytes: Vec<u8> = (0..1200u32).map(|i| (i * 31 + i / 251) as u8).collect();
+                let table = Table::with(&$poly);
+                let hasher = Pclmul::with(&$poly);
+
+                for len in 0..bytes.len() - 16 {
+                    for &offset in &[0, 1, 15] {
+                        let bytes = &bytes[offset..offset + len];
+                        let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                        assert!(table.update_crc(table.initial_value(), bytes) == crc);
+                    }
+                }
+            }
+
+            #[test]
+            fn running_value() {
+                let bytes: Vec<u8> = (0..1000u32).map(|i| (i * 7 + i / 13) as u8).collect();
+                let table = Table::with(&$poly);
+                let hasher = Pclmul::with(&$poly);
+                let crc = table.update_crc(table.initial_value(), &bytes[..333]);
+
+                let expected = table.update_crc(crc, &bytes[333..]);
+                assert!(expected == hasher.update_crc(crc, &bytes[333..]));
+            }
+        }
+    )*)
+}
+
+test_pclmul_hasher!(CRC_8,
+                    CRC_8_MAXIM,
+                    CRC_16,
+                    CRC_16_XMODEM,
+                    CRC_32,
+                    CRC_32_C,
+                    CRC_32_MPEG_2,
+                    CRC_32_Q,
+                    CRC_64,
+                    CRC_64_XZ);
+
+#[cfg(all(test, feature = "use_std", any(target_arch = "x86", target_arch = "x86_64")))]
+#[test]
+fn hardware_detection() {
+    use polynomial::algorithm::CRC_64_XZ;
+
+    let expected = is_x86_feature_detected!("pclmulqdq") && is_x86_feature_detected!("ssse3");
+    assert!(Pclmul::with(&CRC_64_XZ).is_hardware() == expected);
+}
+
+#[cfg(all(test, feature = "use_std"))]
+#[test]
+fn shared_keys() {
+    use std::ptr;
+    use polynomial::algorithm::{CRC_8, CRC_32, CRC_32_C};
+
+    let first: Pclmul<_, _> = Pclmul::with(&CRC_32);
+    let second: Pclmul<_, _> = Pclmul::with(&CRC_32);
+    assert!(ptr::eq(first.keys, second.keys));
+    assert!(!ptr::eq(first.keys, Pclmul::with(&CRC_32_C).keys));
+    assert!(Pclmul::with(&CRC_8).keys.by_1 != first.keys.by_1);
+}
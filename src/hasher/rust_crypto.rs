@@ -0,0 +1,165 @@
+//! Implementations of the RustCrypto `digest` traits for `Digest`.
+//!
+//! The output is the checksum in the byte order of the algorithm of the hasher. Since the
+//! algorithm is chosen at runtime there is no `Default` implementation, so generic code should
+//! use `Update` and `FixedOutput` or a boxed `DynDigest` instead of `digest::Digest`.
+
+use digest::{Update, FixedOutput, FixedOutputReset, Reset, HashMarker, OutputSizeUser, Output};
+use digest::consts::{U1, U2, U4, U8};
+
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
+use polynomial::bytes::CrcBytes;
+use hasher::{CrcHasher, Digest};
+
+impl<'a, H, R, T> Update for Digest<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        Digest::update(self, data);
+    }
+}
+
+impl<'a, H, R, T> Reset for Digest<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    #[inline]
+    fn reset(&mut self) {
+        Digest::reset(self);
+    }
+}
+
+impl<'a, H, R, T> HashMarker for Digest<'a, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+}
+
+macro_rules! doit {
+    ($($ty:ty, $size:ty);*) => ($(
+        impl<'a, H, R> OutputSizeUser for Digest<'a, H, R, $ty>
+            where H: CrcHasher<'a, R, $ty>,
+                  R: 'a + Reflect,
+                  Algorithm<R, $ty>: CrcAlgorithm<$ty>
+        {
+            type OutputSize = $size;
+        }
+
+        impl<'a, H, R> FixedOutput for Digest<'a, H, R, $ty>
+            where H: CrcHasher<'a, R, $ty>,
+                  R: 'a + Reflect,
+                  Algorithm<R, $ty>: CrcAlgorithm<$ty>
+        {
+            fn finalize_into(self, out: &mut Output<Self>) {
+                out.copy_from_slice(&self.hasher().algorithm().encode(self.finalize()));
+            }
+        }
+
+        impl<'a, H, R> FixedOutputReset for Digest<'a, H, R, $ty>
+            where H: CrcHasher<'a, R, $ty>,
+                  R: 'a + Reflect,
+                  Algorithm<R, $ty>: CrcAlgorithm<$ty>
+        {
+            fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                out.copy_from_slice(&self.hasher().algorithm().encode(self.finalize()));
+                Digest::reset(self);
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, U1; Crc16, U2; Crc32, U4; Crc64, U8);
+
+macro_rules! test_rust_crypto {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use digest::{Update, FixedOutput, DynDigest};
+            use hasher::{Digest, Table};
+            use polynomial::algorithm::{ByteOrder, CrcAlgorithm, $poly};
+
+            fn expected() -> Vec<u8> {
+                let (_, check_val) = $poly.check();
+                match $poly.byte_order() {
+                    ByteOrder::LittleEndian => check_val.to_le_bytes().to_vec(),
+                    ByteOrder::BigEndian => check_val.to_be_bytes().to_vec(),
+                }
+            }
+
+            fn generic<D: Update + FixedOutput>(mut digest: D, bytes: &[u8]) -> Vec<u8> {
+                digest.update(bytes);
+                digest.finalize_fixed().to_vec()
+            }
+
+            #[test]
+            fn fixed_output() {
+                let (bytes, _) = $poly.check();
+                let digest: Digest<Table<_, _>, _, _> = Digest::new(&$poly);
+                assert!(generic(digest, bytes) == expected());
+            }
+
+            #[test]
+            fn dyn_digest() {
+                let (bytes, _) = $poly.check();
+                let digest: Digest<Table<_, _>, _, _> = Digest::new(&$poly);
+                let mut digest: Box<dyn DynDigest> = Box::new(digest);
+
+                digest.update(b"garbage");
+                digest.reset();
+                digest.update(bytes);
+                let mut out = vec![0; digest.output_size()];
+                digest.finalize_into_reset(&mut out).unwrap();
+                assert!(out == expected());
+
+                digest.update(bytes);
+                digest.finalize_into_reset(&mut out).unwrap();
+                assert!(out == expected());
+
+                assert!(digest.finalize_into_reset(&mut [0; 9]).is_err());
+            }
+        }
+    )*)
+}
+
+test_rust_crypto!(CRC_8,
+                  CRC_8_MAXIM,
+                  CRC_16,
+                  CRC_16_XMODEM,
+                  CRC_32,
+                  CRC_32_C,
+                  CRC_32_MPEG_2,
+                  CRC_32_Q,
+                  CRC_64,
+                  CRC_64_XZ);
+
+#[cfg(test)]
+mod byte_order {
+    use digest::FixedOutput;
+    use hasher::{CrcHasher, Digest, Table};
+    use polynomial::algorithm::{ByteOrder, CrcAlgorithm, CRC_16, CRC_64};
+
+    #[test]
+    fn overridden() {
+        let (bytes, check_val) = CRC_16.check();
+        let algorithm = CRC_16.with_byte_order(ByteOrder::BigEndian);
+        let mut digest: Digest<Table<_, _>, _, _> = Digest::new(&algorithm);
+        Digest::update(&mut digest, bytes);
+        assert!(digest.finalize_fixed()[..] == check_val.to_be_bytes());
+
+        let (bytes, check_val) = CRC_64.check();
+        let algorithm = CRC_64.with_byte_order(ByteOrder::LittleEndian);
+        let mut digest = Digest::with_hasher(Table::with(&algorithm));
+        Digest::update(&mut digest, bytes);
+        assert!(digest.finalize_fixed()[..] == check_val.to_le_bytes());
+    }
+}
@@ -0,0 +1,463 @@
+//! Table-less hasher multiplying each block of the message by a bit matrix.
+//!
+//! A CRC is linear: once the register has been XORed into the first bytes of a block, the
+//! register after the block is the XOR of the contributions of every set bit of the block. The
+//! contribution of bit b of the byte at position i is a column of a matrix derived from the
+//! polynomial, stored here as byte planes where `planes[k][b][i]` is byte k of that column.
+//! A vector of the block is then turned into one mask per bit, the masks are ANDed with the
+//! planes and XORed together, and a horizontal XOR of each plane leaves one byte of the register.
+//!
+//! Blocks are 32 bytes with AVX2, 16 bytes with SSE2 and 8 bytes held in a `u64` on every other
+//! target. The bytes that do not fill a block are hashed one at a time with the columns of the
+//! last position.
+
+use core::convert::TryInto;
+use core::marker::PhantomData;
+
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use hasher::CrcHasher;
+use hasher::cache;
+
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+
+/// The length of the longest block, which is the number of positions the planes cover.
+const BLOCK_LEN: usize = 32;
+
+/// The instructions used to hash whole blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Portable,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Sse2,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Avx2,
+}
+
+/// The bit matrix of an algorithm with a CRC of type `T`.
+#[derive(Clone)]
+struct Planes<T> {
+    /// Byte k of the register produced by bit b of the byte at position i of a block of
+    /// `BLOCK_LEN` bytes. Shorter blocks use the last positions.
+    planes: [[[u8; BLOCK_LEN]; 8]; 8],
+    /// The register produced by each bit of a single byte.
+    last: [u64; 8],
+    // Keeps the matrices of algorithms of different widths apart in the cache.
+    width: PhantomData<T>,
+}
+
+impl<T> Planes<T> {
+    fn new(polynomial: u64, bits: u32, reflected: bool) -> Self {
+        let mut planes = Planes {
+            planes: [[[0; BLOCK_LEN]; 8]; 8],
+            last: [0; 8],
+            width: PhantomData,
+        };
+
+        for b in 0..8 {
+            let mut column = step(polynomial, bits, reflected, 0, 1 << b);
+            planes.last[b] = column;
+
+            for i in (0..BLOCK_LEN).rev() {
+                for (k, plane) in planes.planes.iter_mut().enumerate() {
+                    plane[b][i] = (column >> (8 * k)) as u8;
+                }
+
+                column = step(polynomial, bits, reflected, column, 0);
+            }
+        }
+
+        planes
+    }
+}
+
+/// Hasher processing whole blocks with vector instructions and no lookup table.
+#[derive(Clone)]
+pub struct Simd<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    algorithm: &'a Algorithm<R, T>,
+    planes: cache::Cached<Planes<T>>,
+    level: Level,
+}
+
+impl<'a, R, T> Simd<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    /// Returns the number of bytes hashed per step: 32 with AVX2, 16 with SSE2 and 8 otherwise.
+    #[inline]
+    pub fn block_len(&self) -> usize {
+        match self.level {
+            Level::Portable => 8,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Level::Sse2 => 16,
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            Level::Avx2 => 32,
+        }
+    }
+}
+
+macro_rules! doit {
+    ($($ty:ty, $bits:expr);*) => ($(
+        impl<'a> CrcHasher<'a, Yes, $ty> for Simd<'a, Yes, $ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                Simd {
+                    algorithm,
+                    planes: cache::cached(algorithm, || {
+                        Planes::new(algorithm.polynomial() as u64, $bits, true)
+                    }),
+                    level: detect(),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                update(&self.planes, self.level, $bits, true, crc as u64, bytes) as $ty
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+
+        impl<'a> CrcHasher<'a, No, $ty> for Simd<'a, No, $ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                Simd {
+                    algorithm,
+                    planes: cache::cached(algorithm, || {
+                        Planes::new(algorithm.polynomial() as u64, $bits, false)
+                    }),
+                    level: detect(),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                update(&self.planes, self.level, $bits, false, crc as u64, bytes) as $ty
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, 8; Crc16, 16; Crc32, 32; Crc64, 64);
+
+#[cfg(all(feature = "use_std", any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn detect() -> Level {
+    if is_x86_feature_detected!("avx2") {
+        Level::Avx2
+    } else if is_x86_feature_detected!("sse2") {
+        Level::Sse2
+    } else {
+        Level::Portable
+    }
+}
+
+// Without std the features can only be known at compile time.
+#[cfg(all(not(feature = "use_std"), any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn detect() -> Level {
+    if cfg!(target_feature = "avx2") {
+        Level::Avx2
+    } else if cfg!(target_feature = "sse2") {
+        Level::Sse2
+    } else {
+        Level::Portable
+    }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn detect() -> Level {
+    Level::Portable
+}
+
+/// Hashes one byte bit by bit. Only used to build the planes.
+fn step(polynomial: u64, bits: u32, reflected: bool, crc: u64, byte: u8) -> u64 {
+    let mut crc = crc;
+
+    if reflected {
+        let polynomial = polynomial.reverse_bits() >> (64 - bits);
+        crc ^= byte as u64;
+
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ ((crc & 1).wrapping_neg() & polynomial);
+        }
+    } else {
+        let mask = u64::MAX >> (64 - bits);
+        crc ^= (byte as u64) << (bits - 8);
+
+        for _ in 0..8 {
+            let top = (crc >> (bits - 1)) & 1;
+            crc = ((crc << 1) & mask) ^ (top.wrapping_neg() & polynomial);
+        }
+    }
+
+    crc
+}
+
+/// Returns the register as a little endian value to XOR into the first bytes of a block.
+#[inline(always)]
+fn seed(bits: u32, reflected: bool, crc: u64) -> u64 {
+    if reflected { crc } else { (crc << (64 - bits)).swap_bytes() }
+}
+
+#[inline(always)]
+fn update<T>(planes: &Planes<T>, level: Level, bits: u32, reflected: bool, crc: u64,
+             bytes: &[u8]) -> u64 {
+    let (crc, rest) = match level {
+        Level::Portable => update_portable(planes, bits, reflected, crc, bytes),
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Level::Sse2 => unsafe { update_sse2(planes, bits, reflected, crc, bytes) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Level::Avx2 => unsafe { update_avx2(planes, bits, reflected, crc, bytes) },
+    };
+
+    let mask = u64::MAX >> (64 - bits);
+
+    rest.iter().fold(crc, |crc, &byte| {
+        let (index, shifted) = if reflected {
+            (byte ^ crc as u8, crc >> 8)
+        } else {
+            (byte ^ (crc >> (bits - 8)) as u8, (crc << 8) & mask)
+        };
+
+        planes.last.iter().enumerate().fold(shifted, |crc, (b, &column)| {
+            crc ^ (column & ((index >> b) as u64 & 1).wrapping_neg())
+        })
+    })
+}
+
+/// Hashes the blocks of 8 bytes of a message with the lanes of a `u64`. Returns the register and
+/// the bytes that are left over.
+#[inline(always)]
+fn update_portable<'b, T>(planes: &Planes<T>, bits: u32, reflected: bool, crc: u64,
+                          bytes: &'b [u8]) -> (u64, &'b [u8]) {
+    let mut crc = crc;
+    let mut blocks = bytes.chunks_exact(8);
+
+    for block in &mut blocks {
+        let value = u64::from_le_bytes(block.try_into().unwrap()) ^ seed(bits, reflected, crc);
+        let mut masks = [0; 8];
+
+        for (b, mask) in masks.iter_mut().enumerate() {
+            *mask = ((value >> b) & 0x0101010101010101) * 0xff;
+        }
+
+        crc = 0;
+
+        for (k, plane) in planes.planes.iter().enumerate().take(bits as usize / 8) {
+            let mut acc = masks.iter().zip(plane).fold(0, |acc, (mask, plane)| {
+                acc ^ (mask & u64::from_le_bytes(plane[BLOCK_LEN - 8..].try_into().unwrap()))
+            });
+
+            acc ^= acc >> 32;
+            acc ^= acc >> 16;
+            acc ^= acc >> 8;
+            crc |= (acc & 0xff) << (8 * k);
+        }
+    }
+
+    (crc, blocks.remainder())
+}
+
+/// Returns the XOR of the 16 bytes of `x`.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+#[target_feature(enable = "sse2")]
+unsafe fn fold_bytes(x: __m128i) -> u64 {
+    let x = _mm_xor_si128(x, _mm_srli_si128(x, 8));
+    let x = _mm_xor_si128(x, _mm_srli_si128(x, 4));
+    let x = _mm_xor_si128(x, _mm_srli_si128(x, 2));
+    let x = _mm_xor_si128(x, _mm_srli_si128(x, 1));
+    (_mm_cvtsi128_si32(x) & 0xff) as u64
+}
+
+/// Hashes the blocks of 16 bytes of a message with SSE2. Returns the register and the bytes that
+/// are left over.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn update_sse2<'b, T>(planes: &Planes<T>, bits: u32, reflected: bool, crc: u64,
+                             bytes: &'b [u8]) -> (u64, &'b [u8]) {
+    let zero = _mm_setzero_si128();
+    let mut crc = crc;
+    let mut blocks = bytes.chunks_exact(16);
+
+    for block in &mut blocks {
+        let mut value = _mm_xor_si128(_mm_loadu_si128(block.as_ptr() as *const __m128i),
+                                      _mm_set_epi64x(0, seed(bits, reflected, crc) as i64));
+        let mut masks = [zero; 8];
+
+        // Doubling every byte moves the next bit into the sign bit, which the comparison spreads
+        // over the whole byte.
+        for mask in masks.iter_mut().rev() {
+            *mask = _mm_cmplt_epi8(value, zero);
+            value = _mm_add_epi8(value, value);
+        }
+
+        crc = 0;
+
+        for (k, plane) in planes.planes.iter().enumerate().take(bits as usize / 8) {
+            let acc = masks.iter().zip(plane).fold(zero, |acc, (&mask, plane)| {
+                let plane = _mm_loadu_si128(plane[BLOCK_LEN - 16..].as_ptr() as *const __m128i);
+                _mm_xor_si128(acc, _mm_and_si128(mask, plane))
+            });
+
+            crc |= fold_bytes(acc) << (8 * k);
+        }
+    }
+
+    (crc, blocks.remainder())
+}
+
+/// Hashes the blocks of 32 bytes of a message with AVX2. Returns the register and the bytes that
+/// are left over.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn update_avx2<'b, T>(planes: &Planes<T>, bits: u32, reflected: bool, crc: u64,
+                             bytes: &'b [u8]) -> (u64, &'b [u8]) {
+    let zero = _mm256_setzero_si256();
+    let mut crc = crc;
+    let mut blocks = bytes.chunks_exact(32);
+
+    for block in &mut blocks {
+        let mut value = _mm256_xor_si256(_mm256_loadu_si256(block.as_ptr() as *const __m256i),
+                                         _mm256_set_epi64x(0, 0, 0,
+                                                           seed(bits, reflected, crc) as i64));
+        let mut masks = [zero; 8];
+
+        for mask in masks.iter_mut().rev() {
+            *mask = _mm256_cmpgt_epi8(zero, value);
+            value = _mm256_add_epi8(value, value);
+        }
+
+        crc = 0;
+
+        for (k, plane) in planes.planes.iter().enumerate().take(bits as usize / 8) {
+            let acc = masks.iter().zip(plane).fold(zero, |acc, (&mask, plane)| {
+                let plane = _mm256_loadu_si256(plane.as_ptr() as *const __m256i);
+                _mm256_xor_si256(acc, _mm256_and_si256(mask, plane))
+            });
+            let acc = _mm_xor_si128(_mm256_castsi256_si128(acc),
+                                    _mm256_extracti128_si256(acc, 1));
+
+            crc |= fold_bytes(acc) << (8 * k);
+        }
+    }
+
+    (crc, blocks.remainder())
+}
+
+/// Returns every level the CPU supports.
+#[cfg(test)]
+fn levels() -> ::std::vec::Vec<Level> {
+    match detect() {
+        Level::Portable => vec![Level::Portable],
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Level::Sse2 => vec![Level::Portable, Level::Sse2],
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Level::Avx2 => vec![Level::Portable, Level::Sse2, Level::Avx2],
+    }
+}
+
+macro_rules! test_simd_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::{Simd, levels};
+            use hasher::{CrcHasher, Bitwise};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+
+                for level in levels() {
+                    let mut hasher = Simd::with(&$poly);
+                    hasher.level = level;
+                    let mut crc = hasher.initial_value();
+                    crc = hasher.update_crc(crc, bytes);
+                    crc = hasher.finalize_crc(crc);
+                    assert!(check_val == crc);
+                }
+            }
+
+            #[test]
+            fn matches_bitwise() {
+                let bytes: Vec<u8> = (0..300u32).map(|i| (i * 31 + i / 251) as u8).collect();
+                let bitwise = Bitwise::with(&$poly);
+                let running = bitwise.update_crc(bitwise.initial_value(), b"prefix");
+
+                for level in levels() {
+                    let mut hasher = Simd::with(&$poly);
+                    hasher.level = level;
+
+                    for len in 0..bytes.len() - 7 {
+                        for &offset in &[0, 1, 7] {
+                            let bytes = &bytes[offset..offset + len];
+                            for &crc in &[bitwise.initial_value(), running] {
+                                assert!(bitwise.update_crc(crc, bytes) ==
+                                        hasher.update_crc(crc, bytes));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    )*)
+}
+
+test_simd_hasher!(CRC_8,
+                  CRC_8_MAXIM,
+                  CRC_16,
+                  CRC_16_XMODEM,
+                  CRC_32,
+                  CRC_32_C,
+                  CRC_32_MPEG_2,
+                  CRC_32_Q,
+                  CRC_64,
+                  CRC_64_XZ);
+
+#[cfg(all(test, feature = "use_std", any(target_arch = "x86", target_arch = "x86_64")))]
+#[test]
+fn detection() {
+    use polynomial::algorithm::CRC_32;
+
+    let expected = if is_x86_feature_detected!("avx2") { 32 } else { 16 };
+    assert!(Simd::with(&CRC_32).block_len() == expected);
+}
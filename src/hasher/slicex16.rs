@@ -0,0 +1,198 @@
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use hasher::CrcHasher;
+use hasher::cache;
+
+
+#[derive(Clone)]
+pub struct Slicex16<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    algorithm: &'a Algorithm<R, T>,
+    table: cache::Cached<[[T; 256]; 16]>,
+}
+
+// The sixteen byte block is read as two words and the register is folded into the first one,
+// the second word only contributes through the tables.
+macro_rules! doit {
+    ($($ty:ty, $bits:expr);*) => ($(
+        impl<'a> CrcHasher<'a, Yes, $ty> for Slicex16<'a, Yes, $ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                Slicex16 {
+                    algorithm,
+                    table: cache::table(algorithm, || [[0; 256]; 16]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+                let mut chunks = bytes.chunks_exact(16);
+                let mut word = [0u8; 8];
+
+                for chunk in &mut chunks {
+                    word.copy_from_slice(&chunk[..8]);
+                    let first = u64::from_le_bytes(word) ^ crc as u64;
+                    word.copy_from_slice(&chunk[8..]);
+                    let second = u64::from_le_bytes(word);
+
+                    crc = self.table[15][(first & 0xFF) as usize] ^
+                          self.table[14][((first >> 8) & 0xFF) as usize] ^
+                          self.table[13][((first >> 16) & 0xFF) as usize] ^
+                          self.table[12][((first >> 24) & 0xFF) as usize] ^
+                          self.table[11][((first >> 32) & 0xFF) as usize] ^
+                          self.table[10][((first >> 40) & 0xFF) as usize] ^
+                          self.table[9][((first >> 48) & 0xFF) as usize] ^
+                          self.table[8][(first >> 56) as usize] ^
+                          self.table[7][(second & 0xFF) as usize] ^
+                          self.table[6][((second >> 8) & 0xFF) as usize] ^
+                          self.table[5][((second >> 16) & 0xFF) as usize] ^
+                          self.table[4][((second >> 24) & 0xFF) as usize] ^
+                          self.table[3][((second >> 32) & 0xFF) as usize] ^
+                          self.table[2][((second >> 40) & 0xFF) as usize] ^
+                          self.table[1][((second >> 48) & 0xFF) as usize] ^
+                          self.table[0][(second >> 56) as usize];
+                }
+
+                for &byte in chunks.remainder() {
+                    crc = (((crc as u64) >> 8) as $ty) ^
+                          self.table[0][((crc as u8) ^ byte) as usize];
+                }
+
+                crc
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+
+        impl<'a> CrcHasher<'a, No, $ty> for Slicex16<'a, No, $ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                Slicex16 {
+                    algorithm,
+                    table: cache::table(algorithm, || [[0; 256]; 16]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+                let mut chunks = bytes.chunks_exact(16);
+                let mut word = [0u8; 8];
+
+                for chunk in &mut chunks {
+                    word.copy_from_slice(&chunk[..8]);
+                    let first = u64::from_be_bytes(word) ^ ((crc as u64) << (64 - $bits));
+                    word.copy_from_slice(&chunk[8..]);
+                    let second = u64::from_be_bytes(word);
+
+                    crc = self.table[15][(first >> 56) as usize] ^
+                          self.table[14][((first >> 48) & 0xFF) as usize] ^
+                          self.table[13][((first >> 40) & 0xFF) as usize] ^
+                          self.table[12][((first >> 32) & 0xFF) as usize] ^
+                          self.table[11][((first >> 24) & 0xFF) as usize] ^
+                          self.table[10][((first >> 16) & 0xFF) as usize] ^
+                          self.table[9][((first >> 8) & 0xFF) as usize] ^
+                          self.table[8][(first & 0xFF) as usize] ^
+                          self.table[7][(second >> 56) as usize] ^
+                          self.table[6][((second >> 48) & 0xFF) as usize] ^
+                          self.table[5][((second >> 40) & 0xFF) as usize] ^
+                          self.table[4][((second >> 32) & 0xFF) as usize] ^
+                          self.table[3][((second >> 24) & 0xFF) as usize] ^
+                          self.table[2][((second >> 16) & 0xFF) as usize] ^
+                          self.table[1][((second >> 8) & 0xFF) as usize] ^
+                          self.table[0][(second & 0xFF) as usize];
+                }
+
+                for &byte in chunks.remainder() {
+                    crc = (((crc as u64) << 8) as $ty) ^
+                          self.table[0][(((crc as u64) >> ($bits - 8)) as u8 ^ byte) as usize];
+                }
+
+                crc
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, 8; Crc16, 16; Crc32, 32; Crc64, 64);
+
+macro_rules! test_slicex16_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::Slicex16;
+            use hasher::CrcHasher;
+            use hasher::bitwise::Bitwise;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Slicex16::with(&$poly);
+                let mut crc = hasher.initial_value();
+                crc = hasher.update_crc(crc, bytes);
+                crc = hasher.finalize_crc(crc);
+                assert!(check_val == crc);
+            }
+
+            #[test]
+            fn unaligned() {
+                let bytes: Vec<u8> = (0..96u32).map(|i| (i * 31 + 7) as u8).collect();
+                let bitwise = Bitwise::with(&$poly);
+                let hasher = Slicex16::with(&$poly);
+
+                for offset in 0..16 {
+                    for len in 0..bytes.len() - offset {
+                        let bytes = &bytes[offset..offset + len];
+                        let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                        assert!(bitwise.update_crc(bitwise.initial_value(), bytes) == crc);
+                    }
+                }
+            }
+        }
+    )*)
+}
+
+test_slicex16_hasher!(CRC_8,
+                      CRC_8_MAXIM,
+                      CRC_16,
+                      CRC_16_XMODEM,
+                      CRC_32,
+                      CRC_32_C,
+                      CRC_32_MPEG_2,
+                      CRC_32_Q,
+                      CRC_64,
+                      CRC_64_XZ);
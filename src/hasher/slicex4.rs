@@ -1,235 +1,124 @@
 use {Crc8, Crc16, Crc32, Crc64};
-use polynomial::{Reflect, Yes, No, CrcPoly, CrcPolynomial};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
 use hasher::CrcHasher;
-use hasher::table_builder::CrcTableBuilder;
-use std::mem;
+use hasher::cache;
 
 
+#[derive(Clone)]
 pub struct Slicex4<'a, R, T>
     where R: 'a + Reflect,
-          T: 'a
+          T: 'static
 {
-    polynomial: &'a CrcPoly<R, T>,
-    table: [[T; 256]; 4],
+    algorithm: &'a Algorithm<R, T>,
+    table: cache::Cached<[[T; 256]; 4]>,
 }
 
+// The register is widened to a u64 where it is shifted so the same code handles every width
+// without overflowing the narrower types: the bits that would be shifted out are simply
+// truncated when the result is cast back.
+macro_rules! doit {
+    ($($ty:ty, $bits:expr);*) => ($(
+        impl<'a> CrcHasher<'a, Yes, $ty> for Slicex4<'a, Yes, $ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                Slicex4 {
+                    algorithm,
+                    table: cache::table(algorithm, || [[0; 256]; 4]),
+                }
+            }
 
-impl<'a> CrcHasher<'a, Yes, Crc8> for Slicex4<'a, Yes, Crc8>
-    where CrcPoly<Yes, Crc8>: CrcPolynomial<Crc8>
-{
-    fn with(polynomial: &'a CrcPoly<Yes, Crc8>) -> Self {
-        let mut hasher = Slicex4 {
-            polynomial: polynomial,
-            table: unsafe { mem::uninitialized() },
-        };
-
-        hasher.table.build_table(polynomial);
-        hasher
-    }
-
-    #[inline]
-    fn initial_value(&self) -> Crc8 {
-        self.polynomial.init_val()
-    }
-
-    fn update_crc(&self, crc: Crc8, bytes: &[u8]) -> Crc8 {
-        let mut crc = crc;
-
-        let mut ptr = bytes.as_ptr();
-        let mut len = bytes.len();
-
-        while len >= 4 {
-            let one = unsafe { *ptr } ^ crc;
-            let two = unsafe { *ptr.offset(1) };
-            let three = unsafe { *ptr.offset(2) };
-            let four = unsafe { *ptr.offset(3) };
-
-            crc = self.table[0][four as usize] ^ self.table[1][three as usize] ^
-                  self.table[2][two as usize] ^ self.table[3][one as usize];
-
-            len -= 4;
-            ptr = unsafe { ptr.offset(4) };
-        }
-
-        while len > 0 {
-            crc = self.table[0][(crc ^ unsafe { *ptr }) as usize];
-
-            len -= 1;
-            ptr = unsafe { ptr.offset(1) };
-        }
-
-        crc
-    }
-
-    #[inline]
-    fn finalize_crc(&self, crc: Crc8) -> Crc8 {
-        self.polynomial.finalize_crc(crc)
-    }
-}
-
-impl<'a> CrcHasher<'a, No, Crc8> for Slicex4<'a, No, Crc8>
-    where CrcPoly<No, Crc8>: CrcPolynomial<Crc8>
-{
-    fn with(polynomial: &'a CrcPoly<No, Crc8>) -> Self {
-        let mut hasher = Slicex4 {
-            polynomial: polynomial,
-            table: unsafe { mem::uninitialized() },
-        };
-
-        hasher.table.build_table(polynomial);
-        hasher
-    }
-
-    #[inline]
-    fn initial_value(&self) -> Crc8 {
-        self.polynomial.init_val()
-    }
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                self.algorithm
+            }
 
-    fn update_crc(&self, crc: Crc8, bytes: &[u8]) -> Crc8 {
-        let mut crc = crc;
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
 
-        let mut ptr = bytes.as_ptr();
-        let mut len = bytes.len();
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+                let mut chunks = bytes.chunks_exact(4);
 
-        while len >= 4 {
-            let one = unsafe { *ptr } ^ crc;
-            let two = unsafe { *ptr.offset(1) };
-            let three = unsafe { *ptr.offset(2) };
-            let four = unsafe { *ptr.offset(3) };
+                for chunk in &mut chunks {
+                    let value = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) ^
+                                crc as u32;
 
-            crc = self.table[0][four as usize] ^ self.table[1][three as usize] ^
-                  self.table[2][two as usize] ^ self.table[3][one as usize];
+                    crc = (((crc as u64) >> 32) as $ty) ^
+                          self.table[3][(value & 0xFF) as usize] ^
+                          self.table[2][((value >> 8) & 0xFF) as usize] ^
+                          self.table[1][((value >> 16) & 0xFF) as usize] ^
+                          self.table[0][(value >> 24) as usize];
+                }
 
-            len -= 4;
-            ptr = unsafe { ptr.offset(4) };
-        }
+                for &byte in chunks.remainder() {
+                    crc = (((crc as u64) >> 8) as $ty) ^
+                          self.table[0][((crc as u8) ^ byte) as usize];
+                }
 
-        while len > 0 {
-            crc = self.table[0][(crc ^ unsafe { *ptr }) as usize];
+                crc
+            }
 
-            len -= 1;
-            ptr = unsafe { ptr.offset(1) };
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
         }
 
-        crc
-    }
-
-    #[inline]
-    fn finalize_crc(&self, crc: Crc8) -> Crc8 {
-        self.polynomial.finalize_crc(crc)
-    }
-}
-
-
-
-
-
-
-
-impl<'a> CrcHasher<'a, Yes, Crc16> for Slicex4<'a, Yes, Crc16>
-    where CrcPoly<Yes, Crc16>: CrcPolynomial<Crc16>
-{
-    fn with(polynomial: &'a CrcPoly<Yes, Crc16>) -> Self {
-        let mut hasher = Slicex4 {
-            polynomial: polynomial,
-            table: unsafe { mem::uninitialized() },
-        };
-
-        hasher.table.build_table(polynomial);
-        hasher
-    }
+        impl<'a> CrcHasher<'a, No, $ty> for Slicex4<'a, No, $ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                Slicex4 {
+                    algorithm,
+                    table: cache::table(algorithm, || [[0; 256]; 4]),
+                }
+            }
 
-    #[inline]
-    fn initial_value(&self) -> Crc16 {
-        self.polynomial.init_val()
-    }
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                self.algorithm
+            }
 
-    fn update_crc(&self, crc: Crc16, bytes: &[u8]) -> Crc16 {
-        let mut crc = crc;
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
 
-        let mut ptr = bytes.as_ptr() as *const Crc16;
-        let mut len = bytes.len();
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+                let mut chunks = bytes.chunks_exact(4);
 
-        while len >= 4 {
-            let one = unsafe { *ptr } ^ crc;
-            let two = unsafe { *ptr.offset(1) };
+                for chunk in &mut chunks {
+                    let value = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) ^
+                                (((crc as u64) << (64 - $bits)) >> 32) as u32;
 
-            crc = self.table[0][four as usize] ^ self.table[1][three as usize] ^
-                  self.table[2][two as usize] ^ self.table[3][one as usize];
+                    crc = (((crc as u64) << 32) as $ty) ^
+                          self.table[3][(value >> 24) as usize] ^
+                          self.table[2][((value >> 16) & 0xFF) as usize] ^
+                          self.table[1][((value >> 8) & 0xFF) as usize] ^
+                          self.table[0][(value & 0xFF) as usize];
+                }
 
-            len -= 4;
-            ptr = unsafe { ptr.offset(2) };
-        }
+                for &byte in chunks.remainder() {
+                    crc = (((crc as u64) << 8) as $ty) ^
+                          self.table[0][(((crc as u64) >> ($bits - 8)) as u8 ^ byte) as usize];
+                }
 
-        while len > 0 {
-            crc = self.table[0][(crc ^ unsafe { *ptr }) as usize];
+                crc
+            }
 
-            len -= 1;
-            ptr = unsafe { ptr.offset(1) };
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
         }
-
-        crc
-    }
-
-    #[inline]
-    fn finalize_crc(&self, crc: Crc16) -> Crc16 {
-        self.polynomial.finalize_crc(crc)
-    }
+    )*)
 }
 
-impl<'a> CrcHasher<'a, No, Crc16> for Slicex4<'a, No, Crc16>
-    where CrcPoly<No, Crc16>: CrcPolynomial<Crc16>
-{
-    fn with(polynomial: &'a CrcPoly<No, Crc16>) -> Self {
-        let mut hasher = Slicex4 {
-            polynomial: polynomial,
-            table: unsafe { mem::uninitialized() },
-        };
-
-        hasher.table.build_table(polynomial);
-        hasher
-    }
-
-    #[inline]
-    fn initial_value(&self) -> Crc16 {
-        self.polynomial.init_val()
-    }
-
-    fn update_crc(&self, crc: Crc16, bytes: &[u8]) -> Crc16 {
-        let mut crc = crc;
-
-        let mut ptr = bytes.as_ptr();
-        let mut idx = 0;
-        let len = bytes.len();
-
-        while idx + 4 <= len {
-            let one = unsafe { *ptr } ^ crc;
-            let two = unsafe { *ptr.offset(1) };
-            let three = unsafe { *ptr.offset(2) };
-            let four = unsafe { *ptr.offset(3) };
-
-            crc = self.table[0][four as usize] ^ self.table[1][three as usize] ^
-                  self.table[2][two as usize] ^ self.table[3][one as usize];
-
-            idx += 4;
-            ptr = unsafe { ptr.offset(4) };
-        }
-
-        while idx < len {
-            crc = self.table[0][(crc ^ unsafe { *ptr }) as usize];
-
-            idx += 1;
-            ptr = unsafe { ptr.offset(1) };
-        }
-
-        crc
-    }
-
-    #[inline]
-    fn finalize_crc(&self, crc: Crc16) -> Crc16 {
-        self.polynomial.finalize_crc(crc)
-    }
-}
+doit!(Crc8, 8; Crc16, 16; Crc32, 32; Crc64, 64);
 
 macro_rules! test_slicex4_hasher {
     ($($poly:ident),*) => ($(
@@ -238,7 +127,9 @@ macro_rules! test_slicex4_hasher {
         mod $poly {
             use super::Slicex4;
             use hasher::CrcHasher;
-            use polynomial::{CrcPolynomial, $poly};
+            use hasher::bitwise::Bitwise;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
 
             #[test]
             fn check() {
@@ -249,16 +140,32 @@ macro_rules! test_slicex4_hasher {
                 crc = hasher.finalize_crc(crc);
                 assert!(check_val == crc);
             }
+
+            #[test]
+            fn unaligned() {
+                let bytes: Vec<u8> = (0..64u32).map(|i| (i * 31 + 7) as u8).collect();
+                let bitwise = Bitwise::with(&$poly);
+                let hasher = Slicex4::with(&$poly);
+
+                for offset in 0..4 {
+                    for len in 0..bytes.len() - offset {
+                        let bytes = &bytes[offset..offset + len];
+                        let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                        assert!(bitwise.update_crc(bitwise.initial_value(), bytes) == crc);
+                    }
+                }
+            }
         }
     )*)
 }
 
 test_slicex4_hasher!(CRC_8,
-                     CRC_8_MAXIM /* CRC_16,
-                                  * CRC_16_XMODEM,
-                                  * CRC_32,
-                                  * CRC_32_C,
-                                  * CRC_32_MPEG_2,
-                                  * CRC_32_Q,
-                                  * CRC_64,
-                                  * CRC_64_XZ */);
+                     CRC_8_MAXIM,
+                     CRC_16,
+                     CRC_16_XMODEM,
+                     CRC_32,
+                     CRC_32_C,
+                     CRC_32_MPEG_2,
+                     CRC_32_Q,
+                     CRC_64,
+                     CRC_64_XZ);
@@ -0,0 +1,178 @@
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
+use hasher::CrcHasher;
+use hasher::cache;
+
+
+#[derive(Clone)]
+pub struct Slicex8<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    algorithm: &'a Algorithm<R, T>,
+    table: cache::Cached<[[T; 256]; 8]>,
+}
+
+// Every register fits in the eight bytes consumed per step, so the whole register is folded into
+// the block and nothing of the old value survives the lookups.
+macro_rules! doit {
+    ($($ty:ty, $bits:expr);*) => ($(
+        impl<'a> CrcHasher<'a, Yes, $ty> for Slicex8<'a, Yes, $ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                Slicex8 {
+                    algorithm,
+                    table: cache::table(algorithm, || [[0; 256]; 8]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+                let mut chunks = bytes.chunks_exact(8);
+                let mut word = [0u8; 8];
+
+                for chunk in &mut chunks {
+                    word.copy_from_slice(chunk);
+                    let value = u64::from_le_bytes(word) ^ crc as u64;
+
+                    crc = self.table[7][(value & 0xFF) as usize] ^
+                          self.table[6][((value >> 8) & 0xFF) as usize] ^
+                          self.table[5][((value >> 16) & 0xFF) as usize] ^
+                          self.table[4][((value >> 24) & 0xFF) as usize] ^
+                          self.table[3][((value >> 32) & 0xFF) as usize] ^
+                          self.table[2][((value >> 40) & 0xFF) as usize] ^
+                          self.table[1][((value >> 48) & 0xFF) as usize] ^
+                          self.table[0][(value >> 56) as usize];
+                }
+
+                for &byte in chunks.remainder() {
+                    crc = (((crc as u64) >> 8) as $ty) ^
+                          self.table[0][((crc as u8) ^ byte) as usize];
+                }
+
+                crc
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+
+        impl<'a> CrcHasher<'a, No, $ty> for Slicex8<'a, No, $ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                Slicex8 {
+                    algorithm,
+                    table: cache::table(algorithm, || [[0; 256]; 8]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                self.algorithm
+            }
+
+            #[inline]
+            fn initial_value(&self) -> $ty {
+                self.algorithm.initial()
+            }
+
+            fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
+                let mut crc = crc;
+                let mut chunks = bytes.chunks_exact(8);
+                let mut word = [0u8; 8];
+
+                for chunk in &mut chunks {
+                    word.copy_from_slice(chunk);
+                    let value = u64::from_be_bytes(word) ^ ((crc as u64) << (64 - $bits));
+
+                    crc = self.table[7][(value >> 56) as usize] ^
+                          self.table[6][((value >> 48) & 0xFF) as usize] ^
+                          self.table[5][((value >> 40) & 0xFF) as usize] ^
+                          self.table[4][((value >> 32) & 0xFF) as usize] ^
+                          self.table[3][((value >> 24) & 0xFF) as usize] ^
+                          self.table[2][((value >> 16) & 0xFF) as usize] ^
+                          self.table[1][((value >> 8) & 0xFF) as usize] ^
+                          self.table[0][(value & 0xFF) as usize];
+                }
+
+                for &byte in chunks.remainder() {
+                    crc = (((crc as u64) << 8) as $ty) ^
+                          self.table[0][(((crc as u64) >> ($bits - 8)) as u8 ^ byte) as usize];
+                }
+
+                crc
+            }
+
+            #[inline]
+            fn finalize_crc(&self, crc: $ty) -> $ty {
+                self.algorithm.finalize_crc(crc)
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, 8; Crc16, 16; Crc32, 32; Crc64, 64);
+
+macro_rules! test_slicex8_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::Slicex8;
+            use hasher::CrcHasher;
+            use hasher::bitwise::Bitwise;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Slicex8::with(&$poly);
+                let mut crc = hasher.initial_value();
+                crc = hasher.update_crc(crc, bytes);
+                crc = hasher.finalize_crc(crc);
+                assert!(check_val == crc);
+            }
+
+            #[test]
+            fn unaligned() {
+                let bytes: Vec<u8> = (0..64u32).map(|i| (i * 31 + 7) as u8).collect();
+                let bitwise = Bitwise::with(&$poly);
+                let hasher = Slicex8::with(&$poly);
+
+                for offset in 0..8 {
+                    for len in 0..bytes.len() - offset {
+                        let bytes = &bytes[offset..offset + len];
+                        let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                        assert!(bitwise.update_crc(bitwise.initial_value(), bytes) == crc);
+                    }
+                }
+            }
+        }
+    )*)
+}
+
+test_slicex8_hasher!(CRC_8,
+                     CRC_8_MAXIM,
+                     CRC_16,
+                     CRC_16_XMODEM,
+                     CRC_32,
+                     CRC_32_C,
+                     CRC_32_MPEG_2,
+                     CRC_32_Q,
+                     CRC_64,
+                     CRC_64_XZ);
@@ -0,0 +1,242 @@
+//! CRC-32C using the `crc32` instruction introduced with SSE4.2.
+//!
+//! The instruction has a latency of three cycles but a throughput of one per cycle, so large
+//! buffers are split in three lanes that are hashed independently and merged afterwards by
+//! shifting the earlier lanes over the length of the later ones.
+
+use Crc32;
+use polynomial::algorithm::{Reflect, Yes, Algorithm, CrcAlgorithm};
+use polynomial::combine::CrcCombine;
+use hasher::{CrcHasher, Table};
+use hasher::cache;
+
+/// Bytes per lane for the interleaved loop over large buffers.
+const LONG: usize = 8192;
+
+/// Bytes per lane for the interleaved loop over what is left after the long lanes.
+const SHORT: usize = 256;
+
+/// Hasher using the SSE4.2 `crc32` instruction when the CPU supports it and the algorithm uses
+/// the CRC-32C polynomial, and a lookup table otherwise.
+#[derive(Clone)]
+pub struct Sse42<'a, R, T>
+    where R: 'a + Reflect,
+          T: 'static
+{
+    algorithm: &'a Algorithm<R, T>,
+    table: Table<'a, R, T>,
+    shift: Option<cache::Cached<ShiftTables<T>>>,
+}
+
+/// The tables advancing a register over a long and a short lane, only built when the hardware
+/// path is taken.
+#[derive(Clone)]
+#[cfg_attr(not(target_arch = "x86_64"), allow(dead_code))]
+struct ShiftTables<T> {
+    long: [[T; 256]; 4],
+    short: [[T; 256]; 4],
+}
+
+impl<'a> CrcHasher<'a, Yes, Crc32> for Sse42<'a, Yes, Crc32>
+    where Algorithm<Yes, Crc32>: CrcAlgorithm<Crc32>
+{
+    fn with(algorithm: &'a Algorithm<Yes, Crc32>) -> Self {
+        let hardware = is_hardware(algorithm);
+
+        Sse42 {
+            algorithm,
+            table: Table::with(algorithm),
+            shift: if hardware {
+                Some(cache::cached(algorithm, || {
+                    ShiftTables {
+                        long: build_shift_table(algorithm, LONG as u64),
+                        short: build_shift_table(algorithm, SHORT as u64),
+                    }
+                }))
+            } else {
+                None
+            },
+        }
+    }
+
+    #[inline]
+    fn algorithm(&self) -> &Algorithm<Yes, Crc32> {
+        self.algorithm
+    }
+
+    #[inline]
+    fn initial_value(&self) -> Crc32 {
+        self.algorithm.initial()
+    }
+
+    fn update_crc(&self, crc: Crc32, bytes: &[u8]) -> Crc32 {
+        match self.shift.as_ref() {
+            Some(shift) => unsafe { update_crc_sse42(shift, crc, bytes) },
+            None => self.table.update_crc(crc, bytes),
+        }
+    }
+
+    #[inline]
+    fn finalize_crc(&self, crc: Crc32) -> Crc32 {
+        self.algorithm.finalize_crc(crc)
+    }
+}
+
+impl<'a> Sse42<'a, Yes, Crc32> {
+    /// Returns true when the hardware path is used by this hasher.
+    #[inline]
+    pub fn is_hardware(&self) -> bool {
+        self.shift.is_some()
+    }
+}
+
+/// Returns true when `algorithm` can use the `crc32` instruction on this CPU.
+#[inline]
+pub fn is_hardware(algorithm: &Algorithm<Yes, Crc32>) -> bool {
+    algorithm.polynomial() == 0x1edc6f41 && has_sse42()
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn update_crc_sse42(tables: &ShiftTables<Crc32>, crc: Crc32, bytes: &[u8]) -> Crc32 {
+    use core::arch::x86_64::{_mm_crc32_u64, _mm_crc32_u8};
+
+    let mut crc = crc;
+    let mut bytes = bytes;
+
+    for &(size, shift) in &[(LONG, &tables.long), (SHORT, &tables.short)] {
+        while bytes.len() >= 3 * size {
+            let mut crc0 = crc as u64;
+            let mut crc1 = 0;
+            let mut crc2 = 0;
+
+            for offset in (0..size).step_by(8) {
+                crc0 = _mm_crc32_u64(crc0, read_u64(&bytes[offset..]));
+                crc1 = _mm_crc32_u64(crc1, read_u64(&bytes[size + offset..]));
+                crc2 = _mm_crc32_u64(crc2, read_u64(&bytes[2 * size + offset..]));
+            }
+
+            crc = apply_shift(shift, crc0 as Crc32) ^ crc1 as Crc32;
+            crc = apply_shift(shift, crc) ^ crc2 as Crc32;
+            bytes = &bytes[3 * size..];
+        }
+    }
+
+    let mut chunks = bytes.chunks_exact(8);
+    let mut crc = crc as u64;
+
+    for chunk in &mut chunks {
+        crc = _mm_crc32_u64(crc, read_u64(chunk));
+    }
+
+    let mut crc = crc as Crc32;
+
+    for &byte in chunks.remainder() {
+        crc = _mm_crc32_u8(crc, byte);
+    }
+
+    crc
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+unsafe fn update_crc_sse42(_: &ShiftTables<Crc32>, _: Crc32, _: &[u8]) -> Crc32 {
+    unreachable!("the hardware path is never taken without SSE4.2")
+}
+
+#[cfg(all(feature = "use_std", target_arch = "x86_64"))]
+#[inline]
+fn has_sse42() -> bool {
+    is_x86_feature_detected!("sse4.2")
+}
+
+// Without std the feature can only be known at compile time.
+#[cfg(all(not(feature = "use_std"), target_arch = "x86_64"))]
+#[inline]
+fn has_sse42() -> bool {
+    cfg!(target_feature = "sse4.2")
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+#[inline]
+fn has_sse42() -> bool {
+    false
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(word)
+}
+
+/// Builds the tables that advance a register over `len` zero bytes one register byte at a time,
+/// which is much cheaper than a full multiplication for every block.
+fn build_shift_table(algorithm: &Algorithm<Yes, Crc32>, len: u64) -> [[Crc32; 256]; 4] {
+    let operator = algorithm.x_pow(8 * len);
+    let mut table = [[0; 256]; 4];
+
+    for (i, table) in table.iter_mut().enumerate() {
+        for (byte, entry) in table.iter_mut().enumerate() {
+            *entry = algorithm.multiply(operator, (byte as Crc32) << (8 * i));
+        }
+    }
+
+    table
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(always)]
+fn apply_shift(table: &[[Crc32; 256]; 4], crc: Crc32) -> Crc32 {
+    table[0][(crc & 0xFF) as usize] ^ table[1][((crc >> 8) & 0xFF) as usize] ^
+    table[2][((crc >> 16) & 0xFF) as usize] ^ table[3][(crc >> 24) as usize]
+}
+
+macro_rules! test_sse42_hasher {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::Sse42;
+            use hasher::{CrcHasher, Table};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Sse42::with(&$poly);
+                let mut crc = hasher.initial_value();
+                crc = hasher.update_crc(crc, bytes);
+                crc = hasher.finalize_crc(crc);
+                assert!(check_val == crc);
+            }
+
+            #[test]
+            fn large() {
+                let bytes: Vec<u8> = (0..60_000u32).map(|i| (i * 31 + i / 251) as u8).collect();
+                let table = Table::with(&$poly);
+                let hasher = Sse42::with(&$poly);
+
+                for &len in &[0, 7, 8, 767, 768, 769, 24_575, 24_576, 24_577, 49_999] {
+                    for offset in 0..8 {
+                        let bytes = &bytes[offset..offset + len];
+                        let crc = hasher.update_crc(hasher.initial_value(), bytes);
+                        assert!(table.update_crc(table.initial_value(), bytes) == crc);
+                    }
+                }
+            }
+        }
+    )*)
+}
+
+test_sse42_hasher!(CRC_32, CRC_32_C);
+
+#[cfg(all(test, feature = "use_std", target_arch = "x86_64"))]
+#[test]
+fn hardware_detection() {
+    use polynomial::algorithm::{CRC_32, CRC_32_C};
+
+    assert!(Sse42::with(&CRC_32_C).is_hardware() == is_x86_feature_detected!("sse4.2"));
+    assert!(!Sse42::with(&CRC_32).is_hardware());
+}
@@ -1,105 +1,162 @@
 use {Crc8, Crc16, Crc32, Crc64};
-use polynomial::{Reflect, Yes, No, CrcPoly, CrcPolynomial};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
 use hasher::CrcHasher;
-use hasher::table_builder::CrcTableBuilder;
-use std::mem;
+use hasher::cache;
 
 
+#[derive(Clone)]
 pub struct Table<'a, R, T>
     where R: 'a + Reflect,
-          T: 'a
+          T: 'static
 {
-    polynomial: &'a CrcPoly<R, T>,
-    table: [T; 256],
+    algorithm: &'a Algorithm<R, T>,
+    table: cache::Cached<[T; 256]>,
+}
+
+/// The number of buffers `update_crc_many` hashes together.
+const LANES: usize = 4;
+
+// Advances a group of `LANES` buffers by one byte each per iteration, so the table lookups of
+// independent buffers overlap instead of each waiting on the previous one. The bytes past the
+// end of the shortest buffer of a group, and the buffers that do not fill a group, go through
+// `rest` one buffer at a time.
+#[inline(always)]
+fn interleave<T, S, U>(crcs: &mut [T], buffers: &[&[u8]], step: S, rest: U)
+    where T: Copy,
+          S: Fn(T, u8) -> T,
+          U: Fn(T, &[u8]) -> T
+{
+    assert!(crcs.len() == buffers.len(), "one CRC is needed per buffer");
+
+    let mut crc_groups = crcs.chunks_exact_mut(LANES);
+    let mut buffer_groups = buffers.chunks_exact(LANES);
+
+    for (crcs, buffers) in (&mut crc_groups).zip(&mut buffer_groups) {
+        let len = buffers.iter().map(|bytes| bytes.len()).min().unwrap_or(0);
+        let mut lanes = [crcs[0]; LANES];
+        lanes.copy_from_slice(crcs);
+
+        for i in 0..len {
+            for (lane, bytes) in lanes.iter_mut().zip(buffers) {
+                *lane = step(*lane, bytes[i]);
+            }
+        }
+
+        for ((crc, &lane), bytes) in crcs.iter_mut().zip(&lanes).zip(buffers) {
+            *crc = rest(lane, &bytes[len..]);
+        }
+    }
+
+    for (crc, bytes) in crc_groups.into_remainder().iter_mut().zip(buffer_groups.remainder()) {
+        *crc = rest(*crc, bytes);
+    }
 }
 
 
 impl<'a> CrcHasher<'a, Yes, Crc8> for Table<'a, Yes, Crc8>
-    where CrcPoly<Yes, Crc8>: CrcPolynomial<Crc8>
+    where Algorithm<Yes, Crc8>: CrcAlgorithm<Crc8>
 {
-    fn with(polynomial: &'a CrcPoly<Yes, Crc8>) -> Self {
-        let mut hasher = Table {
-            polynomial: polynomial,
-            table: unsafe { mem::uninitialized() },
-        };
-
-        hasher.table.build_table(polynomial);
-        hasher
+    fn with(algorithm: &'a Algorithm<Yes, Crc8>) -> Self {
+        Table {
+            algorithm,
+            table: cache::table(algorithm, || [0; 256]),
+        }
+    }
+
+    #[inline]
+    fn algorithm(&self) -> &Algorithm<Yes, Crc8> {
+        self.algorithm
     }
 
     #[inline]
     fn initial_value(&self) -> Crc8 {
-        self.polynomial.init_val()
+        self.algorithm.initial()
     }
 
     fn update_crc(&self, crc: Crc8, bytes: &[u8]) -> Crc8 {
         let mut crc = crc;
 
         for &byte in bytes {
-            crc = self.table[((crc as u8) ^ byte) as usize];
+            crc = self.table[(crc ^ byte) as usize];
         }
 
         crc
     }
 
+    fn update_crc_many(&self, crcs: &mut [Crc8], buffers: &[&[u8]]) {
+        interleave(crcs, buffers,
+                   |crc, byte| self.table[(crc ^ byte) as usize],
+                   |crc, bytes| self.update_crc(crc, bytes));
+    }
+
     #[inline]
     fn finalize_crc(&self, crc: Crc8) -> Crc8 {
-        self.polynomial.finalize_crc(crc)
+        self.algorithm.finalize_crc(crc)
     }
 }
 
 impl<'a> CrcHasher<'a, No, Crc8> for Table<'a, No, Crc8>
-    where CrcPoly<No, Crc8>: CrcPolynomial<Crc8>
+    where Algorithm<No, Crc8>: CrcAlgorithm<Crc8>
 {
-    fn with(polynomial: &'a CrcPoly<No, Crc8>) -> Self {
-        let mut hasher = Table {
-            polynomial: polynomial,
-            table: unsafe { mem::uninitialized() },
-        };
-
-        hasher.table.build_table(polynomial);
-        hasher
+    fn with(algorithm: &'a Algorithm<No, Crc8>) -> Self {
+        Table {
+            algorithm,
+            table: cache::table(algorithm, || [0; 256]),
+        }
+    }
+
+    #[inline]
+    fn algorithm(&self) -> &Algorithm<No, Crc8> {
+        self.algorithm
     }
 
     #[inline]
     fn initial_value(&self) -> Crc8 {
-        self.polynomial.init_val()
+        self.algorithm.initial()
     }
 
     fn update_crc(&self, crc: Crc8, bytes: &[u8]) -> Crc8 {
         let mut crc = crc;
 
         for &byte in bytes {
-            crc = self.table[((crc.rotate_left(8) as u8) ^ byte) as usize];
+            crc = self.table[(crc ^ byte) as usize];
         }
 
         crc
     }
 
+    fn update_crc_many(&self, crcs: &mut [Crc8], buffers: &[&[u8]]) {
+        interleave(crcs, buffers,
+                   |crc, byte| self.table[(crc ^ byte) as usize],
+                   |crc, bytes| self.update_crc(crc, bytes));
+    }
+
     #[inline]
     fn finalize_crc(&self, crc: Crc8) -> Crc8 {
-        self.polynomial.finalize_crc(crc)
+        self.algorithm.finalize_crc(crc)
     }
 }
 
 macro_rules! doit {
     ($($ty:ty),*) => ($(
         impl<'a> CrcHasher<'a, Yes, $ty> for Table<'a, Yes, $ty>
-            where CrcPoly<Yes, $ty>: CrcPolynomial<$ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
         {
-            fn with(polynomial: &'a CrcPoly<Yes, $ty>) -> Self {
-                let mut hasher = Table {
-                    polynomial: polynomial,
-                    table: unsafe { mem::uninitialized() },
-                };
-
-                hasher.table.build_table(polynomial);
-                hasher
+            fn with(algorithm: &'a Algorithm<Yes, $ty>) -> Self {
+                Table {
+                    algorithm,
+                    table: cache::table(algorithm, || [0; 256]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<Yes, $ty> {
+                self.algorithm
             }
 
             #[inline]
             fn initial_value(&self) -> $ty {
-                self.polynomial.init_val()
+                self.algorithm.initial()
             }
 
             fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
@@ -112,28 +169,36 @@ macro_rules! doit {
                 crc
             }
 
+            fn update_crc_many(&self, crcs: &mut [$ty], buffers: &[&[u8]]) {
+                interleave(crcs, buffers,
+                           |crc, byte| (crc >> 8) ^ self.table[((crc as u8) ^ byte) as usize],
+                           |crc, bytes| self.update_crc(crc, bytes));
+            }
+
             #[inline]
             fn finalize_crc(&self, crc: $ty) -> $ty {
-                self.polynomial.finalize_crc(crc)
+                self.algorithm.finalize_crc(crc)
             }
         }
 
         impl<'a> CrcHasher<'a, No, $ty> for Table<'a, No, $ty>
-            where CrcPoly<No, $ty>: CrcPolynomial<$ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
         {
-            fn with(polynomial: &'a CrcPoly<No, $ty>) -> Self {
-                let mut hasher = Table {
-                    polynomial: polynomial,
-                    table: unsafe { mem::uninitialized() },
-                };
-
-                hasher.table.build_table(polynomial);
-                hasher
+            fn with(algorithm: &'a Algorithm<No, $ty>) -> Self {
+                Table {
+                    algorithm,
+                    table: cache::table(algorithm, || [0; 256]),
+                }
+            }
+
+            #[inline]
+            fn algorithm(&self) -> &Algorithm<No, $ty> {
+                self.algorithm
             }
 
             #[inline]
             fn initial_value(&self) -> $ty {
-                self.polynomial.init_val()
+                self.algorithm.initial()
             }
 
             fn update_crc(&self, crc: $ty, bytes: &[u8]) -> $ty {
@@ -146,9 +211,15 @@ macro_rules! doit {
                 crc
             }
 
+            fn update_crc_many(&self, crcs: &mut [$ty], buffers: &[&[u8]]) {
+                interleave(crcs, buffers, |crc, byte| {
+                    (crc << 8) ^ self.table[((crc.rotate_left(8) as u8) ^ byte) as usize]
+                }, |crc, bytes| self.update_crc(crc, bytes));
+            }
+
             #[inline]
             fn finalize_crc(&self, crc: $ty) -> $ty {
-                self.polynomial.finalize_crc(crc)
+                self.algorithm.finalize_crc(crc)
             }
         }
     )*)
@@ -163,7 +234,8 @@ macro_rules! test_table_hasher {
         mod $poly {
             use super::Table;
             use hasher::CrcHasher;
-            use polynomial::{CrcPolynomial, $poly};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::vec::Vec;
 
             #[test]
             fn check() {
@@ -174,6 +246,27 @@ macro_rules! test_table_hasher {
                 crc = hasher.finalize_crc(crc);
                 assert!(check_val == crc);
             }
+
+            #[test]
+            fn many() {
+                let bytes: Vec<u8> = (0..300u32).map(|i| (i * 7 + i / 13) as u8).collect();
+                let buffers: Vec<&[u8]> = [64, 64, 0, 300, 64, 17, 200, 64, 64, 5, 1]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &len)| &bytes[i..len.max(i)])
+                    .collect();
+                let hasher = Table::with(&$poly);
+
+                for count in 0..buffers.len() + 1 {
+                    let buffers = &buffers[..count];
+                    let mut crcs = vec![hasher.initial_value(); count];
+                    hasher.update_crc_many(&mut crcs, buffers);
+
+                    for (&crc, bytes) in crcs.iter().zip(buffers) {
+                        assert!(crc == hasher.update_crc(hasher.initial_value(), bytes));
+                    }
+                }
+            }
         }
     )*)
 }
@@ -1,21 +1,21 @@
 use {Crc8, Crc16, Crc32, Crc64};
-use polynomial::{Reflect, Yes, No, CrcPoly, CrcPolynomial};
+use polynomial::algorithm::{Reflect, Yes, No, Algorithm, CrcAlgorithm};
 use bit_reverse::ParallelReverse;
 
 pub trait CrcTableBuilder<R, T>
     where R: Reflect,
-          CrcPoly<R, T>: CrcPolynomial<T>
+          Algorithm<R, T>: CrcAlgorithm<T>
 {
-    fn build_table(&mut self, polynomial: &CrcPoly<R, T>);
+    fn build_table(&mut self, algorithm: &Algorithm<R, T>);
 }
 
 macro_rules! doit_table {
     ($($ty:ty),*) => ($(
         impl CrcTableBuilder<Yes, $ty> for [$ty; 256]
-            where CrcPoly<Yes, $ty>: CrcPolynomial<$ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
         {
-            fn build_table(&mut self, polynomial: &CrcPoly<Yes, $ty>) {
-                let polynomial = polynomial.poly().swap_bits();
+            fn build_table(&mut self, algorithm: &Algorithm<Yes, $ty>) {
+                let polynomial = algorithm.polynomial().swap_bits();
                 let mut value = polynomial;
 
                 self[0] = 0;
@@ -25,7 +25,7 @@ macro_rules! doit_table {
                 while i > 0 {
                     value = (value >> 1) ^ ((value & (1 as $ty)).wrapping_neg() & polynomial);
                     self[i] = value;
-                    i = i >> 1;
+                    i >>= 1;
                 }
 
                 i = 2;
@@ -35,16 +35,16 @@ macro_rules! doit_table {
                         self[i + j] = temp ^ self[j];
                     }
 
-                    i = i << 1;
+                    i <<= 1;
                 }
             }
         }
 
         impl CrcTableBuilder<No, $ty> for [$ty; 256]
-            where CrcPoly<No, $ty>: CrcPolynomial<$ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
         {
-            fn build_table(&mut self, polynomial: &CrcPoly<No, $ty>) {
-                let polynomial = polynomial.poly();
+            fn build_table(&mut self, algorithm: &Algorithm<No, $ty>) {
+                let polynomial = algorithm.polynomial();
                 let mut value = polynomial;
 
                 self[0] = 0;
@@ -56,7 +56,7 @@ macro_rules! doit_table {
                             ((value & (1 as $ty).rotate_right(1)).rotate_left(1).wrapping_neg() &
                              polynomial);
                     self[i] = value;
-                    i = i << 1;
+                    i <<= 1;
                 }
 
                 i = 2;
@@ -66,7 +66,7 @@ macro_rules! doit_table {
                         self[i + j] = temp ^ self[j];
                     }
 
-                    i = i << 1;
+                    i <<= 1;
                 }
             }
         }
@@ -78,10 +78,10 @@ doit_table!(Crc8, Crc16, Crc32, Crc64);
 macro_rules! doit_slices_crc8 {
     ($($e:expr),*) => ($(
         impl CrcTableBuilder<Yes, Crc8> for [[Crc8; 256]; $e]
-            where CrcPoly<Yes, Crc8>: CrcPolynomial<Crc8>
+            where Algorithm<Yes, Crc8>: CrcAlgorithm<Crc8>
         {
-            fn build_table(&mut self, polynomial: &CrcPoly<Yes, Crc8>) {
-                self[0].build_table(polynomial);
+            fn build_table(&mut self, algorithm: &Algorithm<Yes, Crc8>) {
+                self[0].build_table(algorithm);
 
 
                 for byte in 0..256 {
@@ -94,10 +94,10 @@ macro_rules! doit_slices_crc8 {
         }
 
         impl CrcTableBuilder<No, Crc8> for [[Crc8; 256]; $e]
-            where CrcPoly<No, Crc8>: CrcPolynomial<Crc8>
+            where Algorithm<No, Crc8>: CrcAlgorithm<Crc8>
         {
-            fn build_table(&mut self, polynomial: &CrcPoly<No, Crc8>) {
-                self[0].build_table(polynomial);
+            fn build_table(&mut self, algorithm: &Algorithm<No, Crc8>) {
+                self[0].build_table(algorithm);
 
 
                 for byte in 0..256 {
@@ -120,10 +120,10 @@ macro_rules! doit_slices {
     )*);
     ($ty:ty; $($e:expr),*) => ($(
         impl CrcTableBuilder<Yes, $ty> for [[$ty; 256]; $e]
-            where CrcPoly<Yes, $ty>: CrcPolynomial<$ty>
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
         {
-            fn build_table(&mut self, polynomial: &CrcPoly<Yes, $ty>) {
-                self[0].build_table(polynomial);
+            fn build_table(&mut self, algorithm: &Algorithm<Yes, $ty>) {
+                self[0].build_table(algorithm);
 
 
                 for byte in 0..256 {
@@ -136,10 +136,10 @@ macro_rules! doit_slices {
         }
 
         impl CrcTableBuilder<No, $ty> for [[$ty; 256]; $e]
-            where CrcPoly<No, $ty>: CrcPolynomial<$ty>
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
         {
-            fn build_table(&mut self, polynomial: &CrcPoly<No, $ty>) {
-                self[0].build_table(polynomial);
+            fn build_table(&mut self, algorithm: &Algorithm<No, $ty>) {
+                self[0].build_table(algorithm);
 
 
                 for byte in 0..256 {
@@ -157,6 +157,80 @@ macro_rules! doit_slices {
 
 doit_slices!(Crc16, Crc32, Crc64);
 
+macro_rules! doit_nibbles {
+    ($($ty:ty, $bits:expr);*) => ($(
+        impl CrcTableBuilder<Yes, $ty> for [$ty; 16]
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn build_table(&mut self, algorithm: &Algorithm<Yes, $ty>) {
+                let polynomial = algorithm.polynomial().swap_bits();
+
+                for (nibble, entry) in self.iter_mut().enumerate() {
+                    let mut value = nibble as $ty;
+                    for _ in 0..4 {
+                        value = (value >> 1) ^ ((value & (1 as $ty)).wrapping_neg() & polynomial);
+                    }
+
+                    *entry = value;
+                }
+            }
+        }
+
+        impl CrcTableBuilder<No, $ty> for [$ty; 16]
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn build_table(&mut self, algorithm: &Algorithm<No, $ty>) {
+                let polynomial = algorithm.polynomial();
+
+                for (nibble, entry) in self.iter_mut().enumerate() {
+                    let mut value = (nibble as $ty) << ($bits - 4);
+                    for _ in 0..4 {
+                        value = (value << 1) ^
+                                ((value & (1 as $ty).rotate_right(1)).rotate_left(1).wrapping_neg() &
+                                 polynomial);
+                    }
+
+                    *entry = value;
+                }
+            }
+        }
+
+        impl CrcTableBuilder<Yes, $ty> for [[$ty; 16]; 2]
+            where Algorithm<Yes, $ty>: CrcAlgorithm<$ty>
+        {
+            fn build_table(&mut self, algorithm: &Algorithm<Yes, $ty>) {
+                // The high nibble is shifted out first, so its entries are the nibble table and
+                // the low ones are those advanced over four more zero bits.
+                let mut nibbles: [$ty; 16] = [0; 16];
+                nibbles.build_table(algorithm);
+
+                for (nibble, &value) in nibbles.iter().enumerate() {
+                    self[0][nibble] = (value >> 4) ^ nibbles[(value & 0xF) as usize];
+                    self[1][nibble] = value;
+                }
+            }
+        }
+
+        impl CrcTableBuilder<No, $ty> for [[$ty; 16]; 2]
+            where Algorithm<No, $ty>: CrcAlgorithm<$ty>
+        {
+            fn build_table(&mut self, algorithm: &Algorithm<No, $ty>) {
+                // The low nibble is shifted out first, so its entries are the nibble table and
+                // the high ones are those advanced over four more zero bits.
+                let mut nibbles: [$ty; 16] = [0; 16];
+                nibbles.build_table(algorithm);
+
+                for (nibble, &value) in nibbles.iter().enumerate() {
+                    self[0][nibble] = value;
+                    self[1][nibble] = (value << 4) ^ nibbles[(value.rotate_left(4) & 0xF) as usize];
+                }
+            }
+        }
+    )*)
+}
+
+doit_nibbles!(Crc8, 8; Crc16, 16; Crc32, 32; Crc64, 64);
+
 macro_rules! test_tables {
     ($($poly:ident),*) => ($(
         #[allow(non_snake_case)]
@@ -165,7 +239,7 @@ macro_rules! test_tables {
             use super::CrcTableBuilder;
             use hasher::CrcHasher;
             use hasher::bitwise::Bitwise;
-            use polynomial::$poly;
+            use polynomial::algorithm::$poly;
 
             #[test]
             fn table_check() {
@@ -180,6 +254,18 @@ macro_rules! test_tables {
                 }
             }
 
+            #[test]
+            fn half_byte_check() {
+                let mut table = [0; 256];
+                let mut nibbles = [[0; 16]; 2];
+                table.build_table(&$poly);
+                nibbles.build_table(&$poly);
+
+                for i in 0..256 {
+                    assert!(table[i] == nibbles[0][i & 0xF] ^ nibbles[1][i >> 4]);
+                }
+            }
+
             #[test]
             fn slicex4_check() {
                 let mut bytes = [0u8; 4];
@@ -0,0 +1,718 @@
+//! Adapters computing a CRC over the data going through a reader or a writer.
+
+use std::cmp;
+use std::io::{self, Read, BufRead, Write, ErrorKind, IoSlice};
+use std::mem;
+#[cfg(any(feature = "futures-io", feature = "tokio"))]
+use std::task::{ready, Poll};
+
+use polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
+use polynomial::bytes::CrcBytes;
+use hasher::{CrcHasher, Digest};
+
+#[cfg(feature = "futures-io")]
+mod futures;
+#[cfg(feature = "tokio")]
+mod tokio;
+
+/// Reader computing the CRC of every byte read from the inner reader.
+///
+/// Through `BufRead`, bytes are part of the CRC as soon as `fill_buf` returns them, whether they
+/// are consumed yet or not.
+pub struct CrcReader<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    inner: I,
+    digest: Digest<'a, H, R, T>,
+    /// Length of the start of the inner buffer that is already part of the CRC.
+    hashed: usize,
+}
+
+impl<'a, I, H, R, T> CrcReader<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Wraps `inner`, computing `algorithm` with the hasher `H`.
+    pub fn new(inner: I, algorithm: &'a Algorithm<R, T>) -> Self {
+        CrcReader::with_hasher(inner, H::with(algorithm))
+    }
+
+    /// Wraps `inner`, computing the CRC with `hasher`.
+    pub fn with_hasher(inner: I, hasher: H) -> Self {
+        CrcReader {
+            inner,
+            digest: Digest::with_hasher(hasher),
+            hashed: 0,
+        }
+    }
+
+    /// Returns the CRC of the bytes read so far.
+    #[inline]
+    pub fn crc(&self) -> T {
+        self.digest.finalize()
+    }
+
+    /// Returns the digest of the bytes read so far.
+    #[inline]
+    pub fn digest(&self) -> &Digest<'a, H, R, T> {
+        &self.digest
+    }
+
+    /// Forgets the bytes read so far, the CRC starts over from the next byte read.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.digest.reset();
+        self.hashed = 0;
+    }
+
+    /// Returns a reference to the inner reader.
+    #[inline]
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner reader. Bytes read through it directly are not
+    /// part of the CRC, and must not be among those returned by the last `fill_buf`.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Returns the inner reader.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<'a, I, H, R, T> Read for CrcReader<'a, I, H, R, T>
+    where I: Read,
+          H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        // A buffered reader first hands out what is left of the bytes `fill_buf` hashed.
+        let hashed = cmp::min(self.hashed, len);
+        self.digest.update(&buf[hashed..len]);
+        self.hashed -= hashed;
+        Ok(len)
+    }
+}
+
+impl<'a, I, H, R, T> BufRead for CrcReader<'a, I, H, R, T>
+    where I: BufRead,
+          H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let buf = self.inner.fill_buf()?;
+        if buf.len() > self.hashed {
+            self.digest.update(&buf[self.hashed..]);
+            self.hashed = buf.len();
+        }
+        Ok(buf)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.hashed = self.hashed.saturating_sub(amt);
+        self.inner.consume(amt);
+    }
+}
+
+/// Writer computing the CRC of every byte written to the inner writer.
+pub struct CrcWriter<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    inner: I,
+    digest: Digest<'a, H, R, T>,
+}
+
+impl<'a, I, H, R, T> CrcWriter<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Wraps `inner`, computing `algorithm` with the hasher `H`.
+    pub fn new(inner: I, algorithm: &'a Algorithm<R, T>) -> Self {
+        CrcWriter::with_hasher(inner, H::with(algorithm))
+    }
+
+    /// Wraps `inner`, computing the CRC with `hasher`.
+    pub fn with_hasher(inner: I, hasher: H) -> Self {
+        CrcWriter {
+            inner,
+            digest: Digest::with_hasher(hasher),
+        }
+    }
+
+    /// Returns the CRC of the bytes written so far.
+    #[inline]
+    pub fn crc(&self) -> T {
+        self.digest.finalize()
+    }
+
+    /// Returns the digest of the bytes written so far.
+    #[inline]
+    pub fn digest(&self) -> &Digest<'a, H, R, T> {
+        &self.digest
+    }
+
+    /// Forgets the bytes written so far, the CRC starts over from the next byte written.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.digest.reset();
+    }
+
+    /// Returns a reference to the inner writer.
+    #[inline]
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the inner writer. Bytes written through it directly are not
+    /// part of the CRC.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.inner
+    }
+
+    /// Returns the inner writer.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+
+impl<'a, I, H, R, T> Write for CrcWriter<'a, I, H, R, T>
+    where I: Write,
+          H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        self.digest.update(&buf[..len]);
+        Ok(len)
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice]) -> io::Result<usize> {
+        let len = self.inner.write_vectored(bufs)?;
+        let mut left = len;
+
+        self.digest.update_chunks(bufs.iter().map(|buf| {
+            let written = left.min(buf.len());
+            left -= written;
+            &buf[..written]
+        }));
+        Ok(len)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writer appending the CRC of everything written to it when finished.
+///
+/// The CRC is written in the byte order of the algorithm. With the conventional order of its
+/// reflection, the whole stream checks to the residue of the algorithm.
+///
+/// Closing it asynchronously writes the trailer too, after which writing more data fails with
+/// `ErrorKind::Other`.
+pub struct TrailerWriter<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    writer: CrcWriter<'a, I, H, R, T>,
+    /// How many bytes of the trailer an asynchronous close wrote, once it started.
+    #[cfg(any(feature = "futures-io", feature = "tokio"))]
+    closing: Option<usize>,
+}
+
+impl<'a, I, H, R, T> TrailerWriter<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T> + CrcBytes<T>
+{
+    /// Wraps `inner`, computing `algorithm` with the hasher `H`.
+    pub fn new(inner: I, algorithm: &'a Algorithm<R, T>) -> Self {
+        TrailerWriter::with_hasher(inner, H::with(algorithm))
+    }
+
+    /// Wraps `inner`, computing the CRC with `hasher`.
+    pub fn with_hasher(inner: I, hasher: H) -> Self {
+        TrailerWriter {
+            writer: CrcWriter::with_hasher(inner, hasher),
+            #[cfg(any(feature = "futures-io", feature = "tokio"))]
+            closing: None,
+        }
+    }
+
+    /// Returns the CRC of the bytes written so far.
+    #[inline]
+    pub fn crc(&self) -> T {
+        self.writer.crc()
+    }
+
+    /// Returns a reference to the inner writer.
+    #[inline]
+    pub fn get_ref(&self) -> &I {
+        self.writer.get_ref()
+    }
+
+    /// Writes the CRC after the data, flushes the inner writer and returns it. Nothing is
+    /// appended if the writer is dropped without being finished.
+    pub fn finish(self) -> io::Result<I>
+        where I: Write
+    {
+        let written = self.trailer_written().unwrap_or(0);
+        let crc = self.writer.digest().hasher().algorithm().encode(self.writer.crc());
+        let mut inner = self.writer.into_inner();
+
+        inner.write_all(&crc.as_ref()[written..])?;
+        inner.flush()?;
+        Ok(inner)
+    }
+
+    /// Writes what is left of the trailer through `poll_write`, keeping track of the bytes
+    /// written across calls. No data can be written once it started.
+    #[cfg(any(feature = "futures-io", feature = "tokio"))]
+    fn poll_trailer<F>(&mut self, mut poll_write: F) -> Poll<io::Result<()>>
+        where F: FnMut(&mut I, &[u8]) -> Poll<io::Result<usize>>
+    {
+        let crc = self.writer.digest().hasher().algorithm().encode(self.writer.crc());
+        let written = self.closing.get_or_insert(0);
+
+        while *written < crc.as_ref().len() {
+            match ready!(poll_write(&mut self.writer.inner, &crc.as_ref()[*written..]))? {
+                0 => return Poll::Ready(Err(ErrorKind::WriteZero.into())),
+                len => *written += len,
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<'a, I, H, R, T> TrailerWriter<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    /// Returns how many bytes of the trailer were written, once writing it started.
+    #[cfg(any(feature = "futures-io", feature = "tokio"))]
+    #[inline]
+    fn trailer_written(&self) -> Option<usize> {
+        self.closing
+    }
+
+    /// Returns how many bytes of the trailer were written, once writing it started.
+    #[cfg(not(any(feature = "futures-io", feature = "tokio")))]
+    #[inline]
+    fn trailer_written(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// The error returned for data written after the trailer.
+fn write_after_trailer() -> io::Error {
+    io::Error::other("write after trailer")
+}
+
+impl<'a, I, H, R, T> Write for TrailerWriter<'a, I, H, R, T>
+    where I: Write,
+          H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.trailer_written().is_some() {
+            return Err(write_after_trailer());
+        }
+
+        self.writer.write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Reader checking the CRC trailer written by `TrailerWriter`.
+///
+/// The trailer is withheld from the data returned, and reaching the end of the inner reader
+/// fails with `ErrorKind::InvalidData` if it does not match the CRC of the data, or with
+/// `ErrorKind::UnexpectedEof` if the stream is shorter than the trailer. The data read before
+/// the end is not verified yet, so it must not be trusted until the reader returned `Ok(0)`.
+pub struct TrailerReader<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    inner: I,
+    digest: Digest<'a, H, R, T>,
+    held: [u8; 8],
+    held_len: usize,
+}
+
+impl<'a, I, H, R, T> TrailerReader<'a, I, H, R, T>
+    where H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy + PartialEq,
+          Algorithm<R, T>: CrcAlgorithm<T> + CrcBytes<T>
+{
+    /// Wraps `inner`, computing `algorithm` with the hasher `H`.
+    pub fn new(inner: I, algorithm: &'a Algorithm<R, T>) -> Self {
+        TrailerReader::with_hasher(inner, H::with(algorithm))
+    }
+
+    /// Wraps `inner`, computing the CRC with `hasher`.
+    pub fn with_hasher(inner: I, hasher: H) -> Self {
+        TrailerReader {
+            inner,
+            digest: Digest::with_hasher(hasher),
+            held: [0; 8],
+            held_len: 0,
+        }
+    }
+
+    /// Returns the CRC of the data returned so far.
+    #[inline]
+    pub fn crc(&self) -> T {
+        self.digest.finalize()
+    }
+
+    /// Returns a reference to the inner reader.
+    #[inline]
+    pub fn get_ref(&self) -> &I {
+        &self.inner
+    }
+
+    /// Returns the inner reader.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+
+    fn verify(&self) -> io::Result<()> {
+        if self.held_len < mem::size_of::<T>() {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, "stream shorter than its CRC"));
+        }
+
+        let algorithm = self.digest.hasher().algorithm();
+        if algorithm.decode(&self.held[..self.held_len]) != Some(self.crc()) {
+            return Err(io::Error::new(ErrorKind::InvalidData, "CRC mismatch"));
+        }
+
+        Ok(())
+    }
+
+    /// Takes the bytes just read from the inner reader and moves the ones that can be returned to
+    /// the start of `buf`, returning how many there are.
+    fn withhold(&mut self, buf: &mut [u8]) -> usize {
+        // The stream seen so far ends with the held bytes followed by the new ones: the last
+        // `width` of those are held back and the others are returned, in order.
+        let width = mem::size_of::<T>();
+        let held = self.held_len;
+        let total = held + buf.len();
+        let out = total.saturating_sub(width);
+        let mut tail = [0; 8];
+
+        for (i, byte) in tail[..total - out].iter_mut().enumerate() {
+            let index = out + i;
+            *byte = if index < held { self.held[index] } else { buf[index - held] };
+        }
+
+        if out >= held {
+            buf.copy_within(..out - held, held);
+            buf[..held].copy_from_slice(&self.held[..held]);
+        } else {
+            buf[..out].copy_from_slice(&self.held[..out]);
+        }
+
+        self.held = tail;
+        self.held_len = total - out;
+        self.digest.update(&buf[..out]);
+        out
+    }
+}
+
+impl<'a, I, H, R, T> Read for TrailerReader<'a, I, H, R, T>
+    where I: Read,
+          H: CrcHasher<'a, R, T>,
+          R: 'a + Reflect,
+          T: 'a + Copy + PartialEq,
+          Algorithm<R, T>: CrcAlgorithm<T> + CrcBytes<T>
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        loop {
+            let len = self.inner.read(buf)?;
+            if len == 0 {
+                self.verify()?;
+                return Ok(0);
+            }
+
+            let len = self.withhold(&mut buf[..len]);
+            if len > 0 {
+                return Ok(len);
+            }
+        }
+    }
+}
+
+macro_rules! test_io {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::{CrcReader, CrcWriter, TrailerReader, TrailerWriter};
+            use hasher::{CrcHasher, Table, Slicex8};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use std::io::{self, BufRead, BufReader, ErrorKind, IoSlice, Read, Write};
+
+            /// Reader returning at most three bytes per call.
+            struct Trickle<'b>(&'b [u8]);
+
+            impl<'b> Read for Trickle<'b> {
+                fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                    let len = buf.len().min(self.0.len()).min(3);
+                    buf[..len].copy_from_slice(&self.0[..len]);
+                    self.0 = &self.0[len..];
+                    Ok(len)
+                }
+            }
+
+            #[test]
+            fn reader() {
+                let (bytes, check_val) = $poly.check();
+                let mut reader: CrcReader<_, Table<_, _>, _, _> =
+                    CrcReader::new(Trickle(bytes), &$poly);
+                let mut copy = Vec::new();
+
+                io::copy(&mut reader, &mut copy).unwrap();
+                assert!(copy == bytes);
+                assert!(check_val == reader.crc());
+            }
+
+            #[test]
+            fn buf_reader() {
+                let (bytes, check_val) = $poly.check();
+                let mut reader = CrcReader::with_hasher(BufReader::with_capacity(4, bytes),
+                                                        Slicex8::with(&$poly));
+                let mut lines = String::new();
+
+                while reader.read_line(&mut lines).unwrap() != 0 {}
+                assert!(lines.as_bytes() == bytes);
+                assert!(check_val == reader.crc());
+
+                let mut reader = CrcReader::with_hasher(BufReader::with_capacity(4, bytes),
+                                                        Slicex8::with(&$poly));
+                let mut copy = reader.fill_buf().unwrap().to_vec();
+                reader.consume(1);
+                assert!(reader.fill_buf().unwrap() == &copy[1..]);
+                reader.consume(2);
+                copy.truncate(3);
+                reader.read_to_end(&mut copy).unwrap();
+                assert!(copy == bytes);
+                assert!(check_val == reader.crc());
+            }
+
+            /// Buffered reader handing out its bytes once and failing from then on.
+            struct Once<'b>(Option<&'b [u8]>);
+
+            impl<'b> Read for Once<'b> {
+                fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                    unreachable!()
+                }
+            }
+
+            impl<'b> BufRead for Once<'b> {
+                fn fill_buf(&mut self) -> io::Result<&[u8]> {
+                    self.0.ok_or_else(|| io::Error::other("read twice"))
+                }
+
+                fn consume(&mut self, _: usize) {
+                    self.0 = None;
+                }
+            }
+
+            #[test]
+            fn consume_without_refill() {
+                let (bytes, check_val) = $poly.check();
+                let mut reader: CrcReader<_, Table<_, _>, _, _> =
+                    CrcReader::new(Once(Some(bytes)), &$poly);
+
+                assert!(reader.fill_buf().unwrap() == bytes);
+                reader.consume(bytes.len());
+                assert!(check_val == reader.crc());
+                assert!(reader.fill_buf().unwrap_err().kind() == ErrorKind::Other);
+            }
+
+            fn frame(bytes: &[u8]) -> Vec<u8> {
+                let mut writer: TrailerWriter<_, Table<_, _>, _, _> =
+                    TrailerWriter::new(Vec::new(), &$poly);
+                writer.write_all(bytes).unwrap();
+                writer.finish().unwrap()
+            }
+
+            fn unframe(framed: &[u8]) -> io::Result<Vec<u8>> {
+                let mut reader: TrailerReader<_, Table<_, _>, _, _> =
+                    TrailerReader::new(Trickle(framed), &$poly);
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                Ok(bytes)
+            }
+
+            #[test]
+            fn trailer() {
+                let (bytes, check_val) = $poly.check();
+                let framed = frame(bytes);
+                let width = ::std::mem::size_of_val(&check_val);
+
+                assert!(framed.len() == bytes.len() + width);
+                assert!(framed[..bytes.len()] == bytes[..]);
+                assert!(unframe(&framed).unwrap() == bytes);
+                assert!(unframe(&frame(&[])).unwrap().is_empty());
+
+                let mut reader: TrailerReader<_, Table<_, _>, _, _> =
+                    TrailerReader::new(&framed[..], &$poly);
+                let mut byte = [0; 1];
+                while reader.read(&mut byte).unwrap() != 0 {}
+                assert!(check_val == reader.crc());
+            }
+
+            #[test]
+            fn corrupted_trailer() {
+                let (bytes, _) = $poly.check();
+                let framed = frame(bytes);
+
+                for i in 0..framed.len() {
+                    let mut corrupted = framed.clone();
+                    corrupted[i] ^= 0x10;
+                    let err = unframe(&corrupted).unwrap_err();
+                    assert!(err.kind() == ErrorKind::InvalidData);
+                }
+
+                for len in 0..framed.len() - bytes.len() {
+                    let err = unframe(&framed[..len]).unwrap_err();
+                    assert!(err.kind() == ErrorKind::UnexpectedEof);
+                }
+            }
+
+            #[test]
+            fn writer() {
+                let (bytes, check_val) = $poly.check();
+                let mut writer: CrcWriter<_, Table<_, _>, _, _> =
+                    CrcWriter::new(Vec::new(), &$poly);
+
+                for piece in bytes.chunks(4) {
+                    writer.write_all(piece).unwrap();
+                }
+                writer.flush().unwrap();
+                assert!(check_val == writer.crc());
+
+                writer.reset();
+                let bufs = [IoSlice::new(&bytes[..5]), IoSlice::new(&bytes[5..])];
+                assert!(writer.write_vectored(&bufs).unwrap() == bytes.len());
+                assert!(check_val == writer.crc());
+                assert!(writer.into_inner()[bytes.len()..] == bytes[..]);
+            }
+        }
+    )*)
+}
+
+test_io!(CRC_8,
+         CRC_8_MAXIM,
+         CRC_16,
+         CRC_16_XMODEM,
+         CRC_32,
+         CRC_32_C,
+         CRC_32_MPEG_2,
+         CRC_32_Q,
+         CRC_64,
+         CRC_64_XZ);
+
+#[cfg(test)]
+mod byte_order {
+    use super::{TrailerReader, TrailerWriter};
+    use std::io::{ErrorKind, Read, Write};
+    use hasher::{CrcHasher, Table};
+    use polynomial::algorithm::{ByteOrder, CrcAlgorithm, CRC_16, CRC_32, CRC_32_MPEG_2};
+
+    #[test]
+    fn trailer() {
+        let writer: TrailerWriter<_, Table<_, _>, _, _> = TrailerWriter::new(Vec::new(), &CRC_32);
+        assert!(writer.finish().unwrap() == 0u32.to_le_bytes());
+
+        let mut writer: TrailerWriter<_, Table<_, _>, _, _> =
+            TrailerWriter::new(Vec::new(), &CRC_32_MPEG_2);
+        writer.write_all(b"123456789").unwrap();
+        assert!(writer.finish().unwrap()[9..] == 0x0376e6e7u32.to_be_bytes());
+    }
+
+    #[test]
+    fn overridden() {
+        let (bytes, check_val) = CRC_16.check();
+        let algorithm = CRC_16.with_byte_order(ByteOrder::BigEndian);
+        let mut writer: TrailerWriter<_, Table<_, _>, _, _> =
+            TrailerWriter::new(Vec::new(), &algorithm);
+        writer.write_all(bytes).unwrap();
+        let framed = writer.finish().unwrap();
+        assert!(framed[bytes.len()..] == check_val.to_be_bytes());
+
+        let mut reader: TrailerReader<_, Table<_, _>, _, _> =
+            TrailerReader::new(&framed[..], &algorithm);
+        let mut copy = Vec::new();
+        reader.read_to_end(&mut copy).unwrap();
+        assert!(copy == bytes);
+
+        let mut reader: TrailerReader<_, Table<_, _>, _, _> =
+            TrailerReader::new(&framed[..], &CRC_16);
+        let err = reader.read_to_end(&mut Vec::new()).unwrap_err();
+        assert!(err.kind() == ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn with_hasher() {
+        let (bytes, check_val) = CRC_16.check();
+        let algorithm = CRC_16.with_byte_order(ByteOrder::BigEndian);
+        let mut writer = TrailerWriter::with_hasher(Vec::new(), Table::with(&algorithm));
+        writer.write_all(bytes).unwrap();
+        let framed = writer.finish().unwrap();
+        assert!(framed[bytes.len()..] == check_val.to_be_bytes());
+
+        let mut reader = TrailerReader::with_hasher(&framed[..], Table::with(&algorithm));
+        let mut copy = Vec::new();
+        reader.read_to_end(&mut copy).unwrap();
+        assert!(copy == bytes);
+    }
+}
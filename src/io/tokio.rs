@@ -0,0 +1,218 @@
+//! Implementations of the `tokio` I/O traits for the adapters, for readers and writers that are
+//! `Unpin`.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
+use polynomial::bytes::CrcBytes;
+use hasher::CrcHasher;
+use io::{CrcReader, CrcWriter, TrailerReader, TrailerWriter, write_after_trailer};
+
+impl<'a, I, H, R, T> AsyncRead for CrcReader<'a, I, H, R, T>
+    where I: AsyncRead + Unpin,
+          H: CrcHasher<'a, R, T> + Unpin,
+          R: 'a + Reflect,
+          T: 'a + Copy + Unpin,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut ReadBuf)
+                 -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        this.digest.update(&buf.filled()[before..]);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<'a, I, H, R, T> AsyncWrite for CrcWriter<'a, I, H, R, T>
+    where I: AsyncWrite + Unpin,
+          H: CrcHasher<'a, R, T> + Unpin,
+          R: 'a + Reflect,
+          T: 'a + Copy + Unpin,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8])
+                  -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let len = ready!(Pin::new(&mut this.inner).poll_write(cx, buf))?;
+        this.digest.update(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    #[inline]
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+impl<'a, I, H, R, T> AsyncRead for TrailerReader<'a, I, H, R, T>
+    where I: AsyncRead + Unpin,
+          H: CrcHasher<'a, R, T> + Unpin,
+          R: 'a + Reflect,
+          T: 'a + Copy + PartialEq + Unpin,
+          Algorithm<R, T>: CrcAlgorithm<T> + CrcBytes<T>
+{
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut ReadBuf)
+                 -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            let before = buf.filled().len();
+            ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+            if buf.filled().len() == before {
+                return Poll::Ready(this.verify());
+            }
+
+            let len = this.withhold(&mut buf.filled_mut()[before..]);
+            buf.set_filled(before + len);
+            if len > 0 {
+                return Poll::Ready(Ok(()));
+            }
+        }
+    }
+}
+
+/// Shutting the writer down appends the CRC before shutting the inner writer down.
+impl<'a, I, H, R, T> AsyncWrite for TrailerWriter<'a, I, H, R, T>
+    where I: AsyncWrite + Unpin,
+          H: CrcHasher<'a, R, T> + Unpin,
+          R: 'a + Reflect,
+          T: 'a + Copy + Unpin,
+          Algorithm<R, T>: CrcAlgorithm<T> + CrcBytes<T>
+{
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8])
+                  -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        if this.closing.is_some() {
+            return Poll::Ready(Err(write_after_trailer()));
+        }
+
+        Pin::new(&mut this.writer).poll_write(cx, buf)
+    }
+
+    #[inline]
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().writer).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_trailer(|inner, buf| Pin::new(inner).poll_write(cx, buf)))?;
+        Pin::new(&mut this.writer.inner).poll_shutdown(cx)
+    }
+}
+
+macro_rules! test_tokio {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use io::{CrcReader, CrcWriter, TrailerReader, TrailerWriter};
+            use hasher::Table;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+            use futures::executor::block_on;
+            use std::io::{self, ErrorKind};
+            use tokio::io::{duplex, AsyncReadExt, AsyncWriteExt};
+
+            #[test]
+            fn checksum() {
+                let (bytes, check_val) = $poly.check();
+                let (client, server) = duplex(64);
+
+                let mut writer: CrcWriter<_, Table<_, _>, _, _> = CrcWriter::new(client, &$poly);
+                block_on(writer.write_all(bytes)).unwrap();
+                block_on(writer.shutdown()).unwrap();
+                assert!(check_val == writer.crc());
+
+                let mut reader: CrcReader<_, Table<_, _>, _, _> = CrcReader::new(server, &$poly);
+                let mut copy = Vec::new();
+                block_on(reader.read_to_end(&mut copy)).unwrap();
+                assert!(copy == bytes);
+                assert!(check_val == reader.crc());
+            }
+
+            #[test]
+            fn trailer() {
+                let (bytes, check_val) = $poly.check();
+                let (client, server) = duplex(64);
+
+                let mut writer: TrailerWriter<_, Table<_, _>, _, _> =
+                    TrailerWriter::new(client, &$poly);
+                for piece in bytes.chunks(2) {
+                    block_on(writer.write_all(piece)).unwrap();
+                }
+                block_on(writer.shutdown()).unwrap();
+
+                let mut reader: TrailerReader<_, Table<_, _>, _, _> =
+                    TrailerReader::new(server, &$poly);
+                let mut copy = Vec::new();
+                block_on(reader.read_to_end(&mut copy)).unwrap();
+                assert!(copy == bytes);
+                assert!(check_val == reader.crc());
+            }
+
+            #[test]
+            fn write_after_trailer() {
+                let mut writer: TrailerWriter<_, Table<_, _>, _, _> =
+                    TrailerWriter::new(Vec::new(), &$poly);
+                block_on(writer.write_all(b"data")).unwrap();
+                block_on(writer.shutdown()).unwrap();
+                let framed = writer.get_ref().clone();
+
+                let err = block_on(writer.write_all(b"more")).unwrap_err();
+                assert!(err.kind() == ErrorKind::Other);
+                let err = io::Write::write(&mut writer, b"more").unwrap_err();
+                assert!(err.kind() == ErrorKind::Other);
+                assert!(writer.finish().unwrap() == framed);
+            }
+
+            #[test]
+            fn corrupted_trailer() {
+                let (bytes, _) = $poly.check();
+                let mut writer: TrailerWriter<_, Table<_, _>, _, _> =
+                    TrailerWriter::new(Vec::new(), &$poly);
+                block_on(writer.write_all(bytes)).unwrap();
+                block_on(writer.shutdown()).unwrap();
+                let mut framed = writer.get_ref().clone();
+                let last = framed.len() - 1;
+                framed[last] ^= 1;
+
+                let mut reader: TrailerReader<_, Table<_, _>, _, _> =
+                    TrailerReader::new(&framed[..], &$poly);
+                let err = block_on(reader.read_to_end(&mut Vec::new())).unwrap_err();
+                assert!(err.kind() == ErrorKind::InvalidData);
+
+                let mut reader: TrailerReader<_, Table<_, _>, _, _> =
+                    TrailerReader::new(&framed[..0], &$poly);
+                let err = block_on(reader.read_to_end(&mut Vec::new())).unwrap_err();
+                assert!(err.kind() == ErrorKind::UnexpectedEof);
+            }
+        }
+    )*)
+}
+
+test_tokio!(CRC_8,
+            CRC_8_MAXIM,
+            CRC_16,
+            CRC_16_XMODEM,
+            CRC_32,
+            CRC_32_C,
+            CRC_32_MPEG_2,
+            CRC_32_Q,
+            CRC_64,
+            CRC_64_XZ);
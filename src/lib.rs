@@ -1,7 +1,31 @@
+#![cfg_attr(not(feature = "use_std"), no_std)]
+
+#[cfg(feature = "use_std")]
+extern crate core;
+// The test harness needs std, so tests can use it even where the library itself cannot.
+#[cfg(all(test, not(feature = "use_std")))]
+#[macro_use]
+extern crate std;
 extern crate bit_reverse;
+#[cfg(feature = "digest")]
+extern crate digest;
+#[cfg(feature = "rayon")]
+extern crate rayon;
+#[cfg(feature = "futures-io")]
+extern crate futures_io;
+#[cfg(feature = "tokio")]
+extern crate tokio;
+#[cfg(test)]
+extern crate futures;
 
 pub mod polynomial;
-// pub mod hasher;
+pub mod hasher;
+#[cfg(feature = "use_std")]
+pub mod io;
+#[cfg(feature = "use_std")]
+pub mod parallel;
+#[cfg(feature = "use_std")]
+pub mod many;
 
 pub type Crc8 = u8;
 pub type Crc16 = u16;
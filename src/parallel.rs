@@ -0,0 +1,134 @@
+//! CRC of large buffers computed on several threads.
+//!
+//! The buffer is split in one chunk per thread, the CRC of every chunk is computed on its own
+//! and the results are merged with `CrcCombine`, which only costs a few multiplications per
+//! chunk. Chunks are never made smaller than `MIN_CHUNK_LEN` bytes, so small buffers are hashed
+//! on the calling thread.
+
+use polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
+use polynomial::combine::CrcCombine;
+use hasher::{CrcHasher, Auto};
+
+/// The smallest number of bytes worth handing to another thread.
+pub const MIN_CHUNK_LEN: usize = 64 * 1024;
+
+/// Returns the finalized CRC of `bytes`, splitting the work between at most `threads` threads.
+///
+/// Without the `rayon` feature the calling thread hashes the first chunk and a scoped thread is
+/// spawned for each other one. With it, the chunks are hashed on the global rayon pool instead.
+pub fn parallel_crc<R, T>(algorithm: &Algorithm<R, T>, bytes: &[u8], threads: usize) -> T
+    where R: Reflect + Sync + 'static,
+          T: Copy + Send + 'static,
+          Algorithm<R, T>: CrcAlgorithm<T> + CrcCombine<T> + Sync,
+          for<'a> Auto<'a, R, T>: CrcHasher<'a, R, T>
+{
+    let chunk_len = bytes.len().div_ceil(threads.max(1)).max(MIN_CHUNK_LEN);
+
+    if bytes.len() <= chunk_len {
+        return checksum(algorithm, bytes);
+    }
+
+    let mut crcs = checksum_chunks(algorithm, bytes, chunk_len).into_iter();
+    let first = crcs.next().expect("at least one chunk");
+
+    bytes.chunks(chunk_len)
+        .skip(1)
+        .zip(crcs)
+        .fold(first, |crc, (chunk, chunk_crc)| {
+            algorithm.combine(crc, chunk_crc, chunk.len() as u64)
+        })
+}
+
+fn checksum<R, T>(algorithm: &Algorithm<R, T>, bytes: &[u8]) -> T
+    where R: Reflect,
+          T: 'static,
+          Algorithm<R, T>: CrcAlgorithm<T>,
+          for<'a> Auto<'a, R, T>: CrcHasher<'a, R, T>
+{
+    let hasher = Auto::with(algorithm);
+    hasher.finalize_crc(hasher.update_crc(hasher.initial_value(), bytes))
+}
+
+#[cfg(not(feature = "rayon"))]
+fn checksum_chunks<R, T>(algorithm: &Algorithm<R, T>, bytes: &[u8], chunk_len: usize) -> Vec<T>
+    where R: Reflect + Sync,
+          T: Send + 'static,
+          Algorithm<R, T>: CrcAlgorithm<T> + Sync,
+          for<'a> Auto<'a, R, T>: CrcHasher<'a, R, T>
+{
+    use std::thread;
+
+    let mut chunks = bytes.chunks(chunk_len);
+    let first = chunks.next().unwrap_or(&[]);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = chunks.map(|chunk| scope.spawn(move || checksum(algorithm, chunk)))
+            .collect();
+
+        let mut crcs = Vec::with_capacity(handles.len() + 1);
+        crcs.push(checksum(algorithm, first));
+        crcs.extend(handles.into_iter().map(|handle| handle.join().expect("CRC thread panicked")));
+        crcs
+    })
+}
+
+#[cfg(feature = "rayon")]
+fn checksum_chunks<R, T>(algorithm: &Algorithm<R, T>, bytes: &[u8], chunk_len: usize) -> Vec<T>
+    where R: Reflect + Sync,
+          T: Send + 'static,
+          Algorithm<R, T>: CrcAlgorithm<T> + Sync,
+          for<'a> Auto<'a, R, T>: CrcHasher<'a, R, T>
+{
+    use rayon::prelude::*;
+
+    bytes.par_chunks(chunk_len).map(|chunk| checksum(algorithm, chunk)).collect()
+}
+
+macro_rules! test_parallel {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::{parallel_crc, MIN_CHUNK_LEN};
+            use hasher::{CrcHasher, Table};
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+
+            #[test]
+            fn check() {
+                let (bytes, check_val) = $poly.check();
+                assert!(check_val == parallel_crc(&$poly, bytes, 4));
+                assert!(check_val == parallel_crc(&$poly, bytes, 0));
+            }
+
+            #[test]
+            fn matches_table() {
+                let bytes: Vec<u8> = (0..3 * MIN_CHUNK_LEN as u32 + 1234)
+                    .map(|i| (i * 31 + i / 257) as u8)
+                    .collect();
+                let table = Table::with(&$poly);
+
+                for &len in &[0, 1, MIN_CHUNK_LEN, MIN_CHUNK_LEN + 1, 2 * MIN_CHUNK_LEN - 7,
+                              bytes.len()] {
+                    let bytes = &bytes[..len];
+                    let expected = table.finalize_crc(table.update_crc(table.initial_value(),
+                                                                       bytes));
+
+                    for threads in 1..6 {
+                        assert!(expected == parallel_crc(&$poly, bytes, threads));
+                    }
+                }
+            }
+        }
+    )*)
+}
+
+test_parallel!(CRC_8,
+               CRC_8_MAXIM,
+               CRC_16,
+               CRC_16_XMODEM,
+               CRC_32,
+               CRC_32_C,
+               CRC_32_MPEG_2,
+               CRC_32_Q,
+               CRC_64,
+               CRC_64_XZ);
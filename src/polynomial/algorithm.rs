@@ -1,20 +1,48 @@
 use {Crc8, Crc16, Crc32, Crc64};
 use polynomial::{CrcPolynomial , Normal};
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// The trait that determines if a polynomial is to use the reflected algorithm or not.
-pub trait Reflect {}
+pub trait Reflect {
+    /// True for the reflected algorithm, which stores its checksum least significant byte first.
+    const REFLECTED: bool;
+}
 
 /// Marker for polynomials that are specified to use the reflected algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Yes;
 
 /// Marker for polynomials that are specified to use the non-reflected algorithm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct No;
 
-impl Reflect for Yes {}
-impl Reflect for No {}
+impl Reflect for Yes {
+    const REFLECTED: bool = true;
+}
+
+impl Reflect for No {
+    const REFLECTED: bool = false;
+}
+
+/// The order in which the bytes of a CRC are stored after the message it protects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteOrder {
+    /// Least significant byte first, the usual order for reflected algorithms.
+    LittleEndian,
+    /// Most significant byte first, the usual order for non-reflected algorithms.
+    BigEndian,
+}
+
+impl ByteOrder {
+    /// Returns the order conventionally used with the reflection `R`.
+    #[inline]
+    pub fn conventional<R: Reflect>() -> Self {
+        if R::REFLECTED { ByteOrder::LittleEndian } else { ByteOrder::BigEndian }
+    }
+}
 
 /// A struct that holds all the information needed to compute the CRC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Algorithm<R, T>
     where R: Reflect,
 {
@@ -22,9 +50,27 @@ pub struct Algorithm<R, T>
     polynomial: Normal<T>,
     xor_out: T,
     check: T,
+    byte_order: ByteOrder,
     phantom: PhantomData<R>,
 }
 
+impl<R, T> Algorithm<R, T>
+    where R: Reflect
+{
+    /// Returns the byte order the CRC is stored in on the wire.
+    #[inline]
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+
+    /// Returns the algorithm with its CRC stored in `byte_order` instead, for protocols that do
+    /// not follow the convention of their reflection.
+    #[inline]
+    pub fn with_byte_order(self, byte_order: ByteOrder) -> Self {
+        Algorithm { byte_order, ..self }
+    }
+}
+
 pub trait CrcAlgorithm<T> {
     fn with_parameters<P: Into<Normal<T>>>(init: T, polynomial: P, xor: T, check: T) -> Self;
     fn initial(&self) -> T;
@@ -34,7 +80,7 @@ pub trait CrcAlgorithm<T> {
 }
 
 /// The data used to compute the check value for all CRC polynomials given in this library.
-pub static CHECK_MSG: &'static [u8] = b"123456789";
+pub static CHECK_MSG: &[u8] = b"123456789";
 
 macro_rules! doit {
     ($($ty:ty),*) => ($(
@@ -45,7 +91,8 @@ macro_rules! doit {
                     initial: init,
                     polynomial: polynomial.into(),
                     xor_out: xor,
-                    check: check,
+                    check,
+                    byte_order: ByteOrder::conventional::<R>(),
                     phantom: PhantomData,
                 }
             }
@@ -80,6 +127,7 @@ pub static CRC_8: Algorithm<No, Crc8> = Algorithm {
     polynomial: Normal(0x07),
     xor_out: 0x00,
     check: 0xf4,
+    byte_order: ByteOrder::BigEndian,
     phantom: PhantomData,
 };
 
@@ -88,6 +136,7 @@ pub static CRC_8_MAXIM: Algorithm<Yes, Crc8> = Algorithm {
     polynomial: Normal(0x31),
     xor_out: 0x00,
     check: 0xa1,
+    byte_order: ByteOrder::LittleEndian,
     phantom: PhantomData,
 };
 
@@ -96,6 +145,7 @@ pub static CRC_16: Algorithm<Yes, Crc16> = Algorithm {
     polynomial: Normal(0x8005),
     xor_out: 0,
     check: 0xbb3d,
+    byte_order: ByteOrder::LittleEndian,
     phantom: PhantomData,
 };
 
@@ -104,6 +154,7 @@ pub static CRC_16_XMODEM: Algorithm<No, Crc16> = Algorithm {
     polynomial: Normal(0x1021),
     xor_out: 0,
     check: 0x31c3,
+    byte_order: ByteOrder::BigEndian,
     phantom: PhantomData,
 };
 
@@ -112,6 +163,7 @@ pub static CRC_32: Algorithm<Yes, Crc32> = Algorithm {
     polynomial: Normal(0x04c11db7),
     xor_out: 0xffffffff,
     check: 0xcbf43926,
+    byte_order: ByteOrder::LittleEndian,
     phantom: PhantomData,
 };
 
@@ -120,6 +172,7 @@ pub static CRC_32_C: Algorithm<Yes, Crc32> = Algorithm {
     polynomial: Normal(0x1edc6f41),
     xor_out: 0xffffffff,
     check: 0xe3069283,
+    byte_order: ByteOrder::LittleEndian,
     phantom: PhantomData,
 };
 
@@ -128,6 +181,18 @@ pub static CRC_32_MPEG_2: Algorithm<No, Crc32> = Algorithm {
     polynomial: Normal(0x04c11db7),
     xor_out: 0x00000000,
     check: 0x0376e6e7,
+    byte_order: ByteOrder::BigEndian,
+    phantom: PhantomData,
+};
+
+/// The CRC of POSIX `cksum`, which also hashes the length of the message before finalizing it.
+/// See `hasher::Cksum` for the complete checksum.
+pub static CRC_32_CKSUM: Algorithm<No, Crc32> = Algorithm {
+    initial: 0x00000000,
+    polynomial: Normal(0x04c11db7),
+    xor_out: 0xffffffff,
+    check: 0x765e7680,
+    byte_order: ByteOrder::BigEndian,
     phantom: PhantomData,
 };
 
@@ -136,6 +201,7 @@ pub static CRC_32_Q: Algorithm<No, Crc32> = Algorithm {
     polynomial: Normal(0x814141ab),
     xor_out: 0x00000000,
     check: 0x3010bf7f,
+    byte_order: ByteOrder::BigEndian,
     phantom: PhantomData,
 };
 
@@ -144,6 +210,7 @@ pub static CRC_64: Algorithm<No, Crc64> = Algorithm {
     polynomial: Normal(0x42f0e1eba9ea3693),
     xor_out: 0x0000000000000000,
     check: 0x6c40df5f0b497347,
+    byte_order: ByteOrder::BigEndian,
     phantom: PhantomData,
 };
 
@@ -152,5 +219,6 @@ pub static CRC_64_XZ: Algorithm<Yes, Crc64> = Algorithm {
     polynomial: Normal(0x42f0e1eba9ea3693),
     xor_out: 0xffffffffffffffff,
     check: 0x995dc9bbdf1939fa,
+    byte_order: ByteOrder::LittleEndian,
     phantom: PhantomData,
 };
@@ -0,0 +1,138 @@
+//! Serialization of CRCs in the byte order of their algorithm.
+//!
+//! Framing code stores a CRC next to the message it protects. The algorithm knows which order
+//! its checksum is conventionally stored in, so these helpers spare callers from choosing
+//! between `to_le_bytes` and `to_be_bytes` themselves.
+
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Reflect, ByteOrder, Algorithm};
+
+pub trait CrcBytes<T> {
+    /// The serialized form of a CRC, as many bytes as the width of the algorithm.
+    type Bytes: AsRef<[u8]>;
+
+    /// Returns the bytes of `crc` in the byte order of the algorithm.
+    fn encode(&self, crc: T) -> Self::Bytes;
+
+    /// Reads back a CRC serialized by `encode`, or returns `None` if `bytes` is not exactly as
+    /// long as the width of the algorithm.
+    fn decode(&self, bytes: &[u8]) -> Option<T>;
+
+    /// Appends the bytes of `crc` to `out`.
+    #[cfg(feature = "use_std")]
+    fn append(&self, crc: T, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.encode(crc).as_ref());
+    }
+}
+
+macro_rules! doit {
+    ($($ty:ty, $len:expr);*) => ($(
+        impl<R> CrcBytes<$ty> for Algorithm<R, $ty>
+            where R: Reflect
+        {
+            type Bytes = [u8; $len];
+
+            #[inline]
+            fn encode(&self, crc: $ty) -> [u8; $len] {
+                match self.byte_order() {
+                    ByteOrder::LittleEndian => crc.to_le_bytes(),
+                    ByteOrder::BigEndian => crc.to_be_bytes(),
+                }
+            }
+
+            #[inline]
+            fn decode(&self, bytes: &[u8]) -> Option<$ty> {
+                if bytes.len() != $len {
+                    return None;
+                }
+
+                let mut array = [0; $len];
+                array.copy_from_slice(bytes);
+
+                match self.byte_order() {
+                    ByteOrder::LittleEndian => Some(<$ty>::from_le_bytes(array)),
+                    ByteOrder::BigEndian => Some(<$ty>::from_be_bytes(array)),
+                }
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, 1; Crc16, 2; Crc32, 4; Crc64, 8);
+
+macro_rules! test_bytes {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::CrcBytes;
+            use polynomial::algorithm::{ByteOrder, CrcAlgorithm, $poly};
+
+            #[test]
+            fn round_trip() {
+                let (_, check_val) = $poly.check();
+                let bytes = $poly.encode(check_val);
+                assert!($poly.decode(bytes.as_ref()) == Some(check_val));
+
+                let expected = match $poly.byte_order() {
+                    ByteOrder::LittleEndian => check_val.to_le_bytes(),
+                    ByteOrder::BigEndian => check_val.to_be_bytes(),
+                };
+                assert!(bytes == expected);
+            }
+
+            #[test]
+            fn wrong_length() {
+                let (_, check_val) = $poly.check();
+                let mut bytes = $poly.encode(check_val).to_vec();
+                assert!($poly.decode(&bytes[1..]).is_none());
+                bytes.push(0);
+                assert!($poly.decode(&bytes).is_none());
+            }
+
+            #[cfg(feature = "use_std")]
+            #[test]
+            fn append() {
+                let (message, check_val) = $poly.check();
+                let mut framed = message.to_vec();
+                $poly.append(check_val, &mut framed);
+                assert!(framed[..message.len()] == message[..]);
+                assert!($poly.decode(&framed[message.len()..]) == Some(check_val));
+            }
+
+            #[test]
+            fn override_byte_order() {
+                let (_, check_val) = $poly.check();
+                let little = $poly.with_byte_order(ByteOrder::LittleEndian);
+                let big = $poly.with_byte_order(ByteOrder::BigEndian);
+                assert!(little.encode(check_val) == check_val.to_le_bytes());
+                assert!(big.encode(check_val) == check_val.to_be_bytes());
+                assert!(little.check() == $poly.check());
+            }
+        }
+    )*)
+}
+
+test_bytes!(CRC_8,
+            CRC_8_MAXIM,
+            CRC_16,
+            CRC_16_XMODEM,
+            CRC_32,
+            CRC_32_C,
+            CRC_32_MPEG_2,
+            CRC_32_Q,
+            CRC_64,
+            CRC_64_XZ);
+
+#[cfg(test)]
+mod tests {
+    use polynomial::algorithm::{ByteOrder, CRC_16, CRC_16_XMODEM, CRC_32, CRC_64};
+
+    #[test]
+    fn conventional_order() {
+        assert!(CRC_16.byte_order() == ByteOrder::LittleEndian);
+        assert!(CRC_32.byte_order() == ByteOrder::LittleEndian);
+        assert!(CRC_16_XMODEM.byte_order() == ByteOrder::BigEndian);
+        assert!(CRC_64.byte_order() == ByteOrder::BigEndian);
+    }
+}
@@ -0,0 +1,168 @@
+//! Arithmetic on CRC registers modulo the polynomial of an algorithm.
+//!
+//! A register is treated as a polynomial of degree less than the width of the CRC, in the same
+//! bit order the hashers use for their running value: the lowest bit holds x^0 for non-reflected
+//! algorithms and x^(width - 1) for reflected ones.
+
+use {Crc8, Crc16, Crc32, Crc64};
+use polynomial::algorithm::{Yes, No, Algorithm, CrcAlgorithm};
+use bit_reverse::ParallelReverse;
+
+pub trait CrcCombine<T> {
+    /// Returns x^n modulo the polynomial.
+    fn x_pow(&self, n: u64) -> T;
+
+    /// Multiplies two registers modulo the polynomial.
+    fn multiply(&self, a: T, b: T) -> T;
+
+    /// Advances a running (unfinalized) CRC over `len` zero bytes.
+    fn shift(&self, crc: T, len: u64) -> T;
+
+    /// Computes the finalized CRC of the concatenation of two messages given the finalized CRC of
+    /// each one and the length in bytes of the second.
+    fn combine(&self, crc1: T, crc2: T, len2: u64) -> T;
+}
+
+macro_rules! doit {
+    ($($ty:ty),*) => ($(
+        impl CrcCombine<$ty> for Algorithm<Yes, $ty> {
+            fn x_pow(&self, n: u64) -> $ty {
+                let mut result = (1 as $ty).rotate_right(1);
+                let mut base = result >> 1;
+                let mut n = n;
+
+                while n > 0 {
+                    if n & 1 == 1 {
+                        result = self.multiply(result, base);
+                    }
+
+                    base = self.multiply(base, base);
+                    n >>= 1;
+                }
+
+                result
+            }
+
+            fn multiply(&self, a: $ty, b: $ty) -> $ty {
+                let polynomial = self.polynomial().swap_bits();
+                let mut product = 0;
+                let mut a = a;
+                let mut b = b;
+
+                while a != 0 {
+                    product ^= (a & (1 as $ty).rotate_right(1)).rotate_left(1).wrapping_neg() & b;
+                    a <<= 1;
+                    b = (b >> 1) ^ ((b & 1).wrapping_neg() & polynomial);
+                }
+
+                product
+            }
+
+            fn shift(&self, crc: $ty, len: u64) -> $ty {
+                self.multiply(crc, self.x_pow(8 * len))
+            }
+
+            fn combine(&self, crc1: $ty, crc2: $ty, len2: u64) -> $ty {
+                let crc1 = self.finalize_crc(crc1) ^ self.initial();
+                self.shift(crc1, len2) ^ crc2
+            }
+        }
+
+        impl CrcCombine<$ty> for Algorithm<No, $ty> {
+            fn x_pow(&self, n: u64) -> $ty {
+                let mut result = 1;
+                let mut base = 2;
+                let mut n = n;
+
+                while n > 0 {
+                    if n & 1 == 1 {
+                        result = self.multiply(result, base);
+                    }
+
+                    base = self.multiply(base, base);
+                    n >>= 1;
+                }
+
+                result
+            }
+
+            fn multiply(&self, a: $ty, b: $ty) -> $ty {
+                let polynomial = self.polynomial();
+                let mut product = 0;
+                let mut a = a;
+                let mut b = b;
+
+                while a != 0 {
+                    product ^= (a & 1).wrapping_neg() & b;
+                    a >>= 1;
+                    b = (b << 1) ^
+                        ((b & (1 as $ty).rotate_right(1)).rotate_left(1).wrapping_neg() &
+                         polynomial);
+                }
+
+                product
+            }
+
+            fn shift(&self, crc: $ty, len: u64) -> $ty {
+                self.multiply(crc, self.x_pow(8 * len))
+            }
+
+            fn combine(&self, crc1: $ty, crc2: $ty, len2: u64) -> $ty {
+                let crc1 = self.finalize_crc(crc1) ^ self.initial();
+                self.shift(crc1, len2) ^ crc2
+            }
+        }
+    )*)
+}
+
+doit!(Crc8, Crc16, Crc32, Crc64);
+
+macro_rules! test_combine {
+    ($($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        #[cfg(test)]
+        mod $poly {
+            use super::CrcCombine;
+            use hasher::CrcHasher;
+            use hasher::bitwise::Bitwise;
+            use polynomial::algorithm::{CrcAlgorithm, $poly};
+
+            #[test]
+            fn combine() {
+                let (bytes, check_val) = $poly.check();
+                let hasher = Bitwise::with(&$poly);
+
+                for split in 0..bytes.len() + 1 {
+                    let (first, second) = bytes.split_at(split);
+                    let crc1 = hasher.finalize_crc(hasher.update_crc(hasher.initial_value(), first));
+                    let crc2 = hasher.finalize_crc(hasher.update_crc(hasher.initial_value(), second));
+                    assert!(check_val == $poly.combine(crc1, crc2, second.len() as u64));
+                }
+            }
+
+            #[test]
+            fn shift() {
+                let zeros = [0u8; 300];
+                let hasher = Bitwise::with(&$poly);
+                let crc = hasher.update_crc(hasher.initial_value(), $poly.check().0);
+
+                for &len in &[0, 1, 2, 7, 8, 9, 100, 300] {
+                    let expected = hasher.update_crc(crc, &zeros[..len]);
+                    assert!(expected == $poly.shift(crc, len as u64));
+                    assert!(expected == $poly.multiply(crc, $poly.x_pow(8 * len as u64)));
+                }
+            }
+        }
+    )*)
+}
+
+test_combine!(CRC_8,
+              CRC_8_MAXIM,
+              CRC_16,
+              CRC_16_XMODEM,
+              CRC_32,
+              CRC_32_C,
+              CRC_32_MPEG_2,
+              CRC_32_Q,
+              CRC_64,
+              CRC_64_XZ);
@@ -2,12 +2,14 @@
 //! in memory and how to convert between each form.
 
 pub mod algorithm;
+pub mod combine;
+pub mod bytes;
 
 use {Crc8, Crc16, Crc32, Crc64};
 use bit_reverse::ParallelReverse;
-use std::convert::From;
-use std::option::Option;
-use std::marker::Sized;
+use core::convert::From;
+use core::option::Option;
+use core::marker::Sized;
 
 /// Struct that holds a CRC polynomial in Most Significant Bit order.
 ///
@@ -0,0 +1,243 @@
+//! Differential conformance suite for the hashers.
+//!
+//! Every registered hasher is compared with a naive reference computing the CRC by long division
+//! of the message by the polynomial, one bit at a time, over messages of random lengths, at
+//! random alignments and fed in random pieces. A new hasher is covered by adding one
+//! `conformance_suite!` line at the bottom of this file, and to the `cksum_suite!` list which
+//! checks `Cksum` on top of every hasher.
+
+extern crate crc_complete;
+
+use std::mem;
+
+use crc_complete::hasher::{CrcHasher, Cksum};
+use crc_complete::polynomial::algorithm::{Reflect, Algorithm, CrcAlgorithm};
+
+/// The number of random messages checked per hasher and algorithm.
+const CASES: usize = 32;
+
+/// The longest message checked.
+const MAX_LEN: usize = 8192;
+
+/// The largest offset of a message from the start of its buffer.
+const MAX_OFFSET: usize = 64;
+
+/// Xorshift generator with a fixed seed, so a failure reproduces on every run.
+struct Xorshift(u64);
+
+impl Xorshift {
+    fn new() -> Self {
+        Xorshift(0x9e3779b97f4a7c15)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Returns a number in `0..=max`.
+    fn up_to(&mut self, max: usize) -> usize {
+        (self.next() % (max as u64 + 1)) as usize
+    }
+}
+
+/// Computes the CRC of `bytes` as the remainder of the division of the message, followed by as
+/// many zero bits as the width and with the initial value XORed into its first bits, by the
+/// polynomial.
+fn reference<R, T>(algorithm: &Algorithm<R, T>, bytes: &[u8]) -> u64
+    where R: Reflect,
+          T: Default + Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    let width = 8 * mem::size_of::<T>();
+    let polynomial = algorithm.polynomial().into();
+    let initial = algorithm.initial().into();
+
+    // Bits in the order they are transmitted: least significant first for reflected algorithms.
+    let mut bits: Vec<bool> = bytes.iter()
+        .flat_map(|&byte| {
+            (0..8).map(move |i| {
+                let shift = if R::REFLECTED { i } else { 7 - i };
+                (byte >> shift) & 1 == 1
+            })
+        })
+        .chain((0..width).map(|_| false))
+        .collect();
+
+    for (i, bit) in bits[..width].iter_mut().enumerate() {
+        let shift = if R::REFLECTED { i } else { width - 1 - i };
+        *bit ^= (initial >> shift) & 1 == 1;
+    }
+
+    let len = bits.len() - width;
+
+    for i in 0..len {
+        if bits[i] {
+            for j in 1..width + 1 {
+                bits[i + j] ^= (polynomial >> (width - j)) & 1 == 1;
+            }
+        }
+    }
+
+    let remainder = bits[len..].iter().enumerate().fold(0, |crc, (j, &bit)| {
+        let shift = if R::REFLECTED { j } else { width - 1 - j };
+        crc | (bit as u64) << shift
+    });
+
+    // Finalizing zero leaves the value the remainder is XORed with.
+    remainder ^ algorithm.finalize_crc(T::default()).into()
+}
+
+/// Checks the hasher `H` against the reference for `algorithm`.
+fn check_hasher<'a, H, R, T>(algorithm: &'a Algorithm<R, T>)
+    where H: CrcHasher<'a, R, T>,
+          R: Reflect,
+          T: Copy + Default + Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    let (check, check_val) = algorithm.check();
+    assert!(reference(algorithm, check) == check_val.into(), "reference disagrees with check");
+
+    let hasher = H::with(algorithm);
+    let mut rng = Xorshift::new();
+    let buffer: Vec<u8> = (0..MAX_LEN + MAX_OFFSET).map(|_| rng.next() as u8).collect();
+
+    for _ in 0..CASES {
+        // Favour short messages, where the hashers switch between their code paths.
+        let max = [64, 256, 1024, MAX_LEN][rng.up_to(3)];
+        let len = rng.up_to(max);
+        let offset = rng.up_to(MAX_OFFSET);
+        let bytes = &buffer[offset..offset + len];
+        let expected = reference(algorithm, bytes);
+
+        let crc = hasher.update_crc(hasher.initial_value(), bytes);
+        assert!(hasher.finalize_crc(crc).into() == expected,
+                "{} bytes at offset {}", len, offset);
+
+        let first = rng.up_to(len);
+        let second = first + rng.up_to(len - first);
+        let mut crc = hasher.initial_value();
+        crc = hasher.update_crc(crc, &bytes[..first]);
+        crc = hasher.update_crc(crc, &bytes[first..second]);
+        crc = hasher.update_crc(crc, &bytes[second..]);
+        assert!(hasher.finalize_crc(crc).into() == expected,
+                "{} bytes at offset {} split at {} and {}", len, offset, first, second);
+    }
+}
+
+/// Checks `Cksum` with the hasher `H` against the reference for `algorithm` run over the message
+/// followed by its length, least significant byte first and without the zero bytes above it.
+fn check_cksum<'a, H, R, T>(algorithm: &'a Algorithm<R, T>)
+    where H: CrcHasher<'a, R, T> + Clone,
+          R: Reflect,
+          T: Copy + Default + Into<u64>,
+          Algorithm<R, T>: CrcAlgorithm<T>
+{
+    let mut rng = Xorshift::new();
+    let buffer: Vec<u8> = (0..MAX_LEN).map(|_| rng.next() as u8).collect();
+
+    for _ in 0..CASES {
+        let max = [64, 256, 1024, MAX_LEN][rng.up_to(3)];
+        let len = rng.up_to(max);
+        let bytes = &buffer[..len];
+
+        let mut message = bytes.to_vec();
+        let mut remaining = len as u64;
+        while remaining > 0 {
+            message.push(remaining as u8);
+            remaining >>= 8;
+        }
+        let expected = reference(algorithm, &message);
+
+        let split = rng.up_to(len);
+        let mut cksum: Cksum<H, R, T> = Cksum::new(algorithm);
+        cksum.update(&bytes[..split]);
+        cksum.update(&bytes[split..]);
+        assert!(cksum.finalize().into() == expected, "{} bytes split at {}", len, split);
+        assert!(cksum.len() == len as u64);
+    }
+}
+
+macro_rules! conformance_suite {
+    ($hasher:ident) => (
+        #[allow(non_snake_case)]
+        mod $hasher {
+            conformance_suite!($hasher;
+                               CRC_8,
+                               CRC_8_MAXIM,
+                               CRC_16,
+                               CRC_16_XMODEM,
+                               CRC_32,
+                               CRC_32_C,
+                               CRC_32_MPEG_2,
+                               CRC_32_CKSUM,
+                               CRC_32_Q,
+                               CRC_64,
+                               CRC_64_XZ);
+        }
+    );
+    ($hasher:ident; $($poly:ident),*) => ($(
+        #[allow(non_snake_case)]
+        mod $poly {
+            use super::super::check_hasher;
+            use crc_complete::polynomial::algorithm::$poly;
+            use crc_complete::hasher::$hasher;
+
+            #[test]
+            fn conformance() {
+                check_hasher::<$hasher<_, _>, _, _>(&$poly);
+            }
+        }
+    )*)
+}
+
+conformance_suite!(Bitwise);
+conformance_suite!(Table);
+conformance_suite!(Nibble);
+conformance_suite!(HalfByte);
+conformance_suite!(Slicex4);
+conformance_suite!(Slicex8);
+conformance_suite!(Slicex16);
+conformance_suite!(Pclmul);
+conformance_suite!(Simd);
+conformance_suite!(Auto);
+conformance_suite!(OwnedBitwise);
+conformance_suite!(OwnedTable);
+
+#[allow(non_snake_case)]
+mod Sse42 {
+    conformance_suite!(Sse42; CRC_32, CRC_32_C);
+}
+
+macro_rules! cksum_suite {
+    ($($hasher:ident),*) => (
+        #[allow(non_snake_case)]
+        mod cksum {
+            use super::check_cksum;
+            use crc_complete::polynomial::algorithm::CRC_32_CKSUM;
+
+            $(
+                #[test]
+                fn $hasher() {
+                    use crc_complete::hasher::$hasher;
+                    check_cksum::<$hasher<_, _>, _, _>(&CRC_32_CKSUM);
+                }
+            )*
+        }
+    )
+}
+
+cksum_suite!(Bitwise,
+             Table,
+             Nibble,
+             HalfByte,
+             Slicex4,
+             Slicex8,
+             Slicex16,
+             Pclmul,
+             Simd,
+             Auto,
+             OwnedBitwise,
+             OwnedTable);